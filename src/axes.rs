@@ -0,0 +1,55 @@
+use kurbo::{Ellipse, Point, Vec2};
+
+/// The principal axes of a shape, derived from its covariance matrix
+#[derive(Debug, Copy, Clone)]
+pub struct PrincipalAxes {
+    /// The eigenvalues of the covariance matrix, i.e. the variance along the
+    /// major and minor axes respectively (largest first)
+    pub eigenvalues: (f64, f64),
+    /// The unit eigenvectors of the covariance matrix, in the same order as
+    /// the eigenvalues
+    pub eigenvectors: (Vec2, Vec2),
+    /// The angle of the major axis in radians, measured anticlockwise from the x axis
+    /// and normalized to `-π/2..=π/2`
+    pub angle: f64,
+    /// The eccentricity of the equivalent ellipse; zero for an isotropic shape,
+    /// approaching one for a shape stretched along a line
+    pub eccentricity: f64,
+    /// The uniform ellipse with the same center of mass and second moments as the shape
+    pub ellipse: Ellipse,
+}
+
+impl PrincipalAxes {
+    /// Compute the principal axes from a center of mass and a covariance matrix
+    ///
+    /// The covariance matrix is given as the variance along each axis and
+    /// the covariance between the axes.
+    pub fn new(center: Point, variance: Vec2, covariance: f64) -> Self {
+        let mean = (variance.x + variance.y) / 2.0;
+        let spread = ((variance.x - variance.y) / 2.0).hypot(covariance);
+        let major = mean + spread;
+        // Guard against tiny negative values from rounding on degenerate shapes
+        let minor = (mean - spread).max(0.0);
+        let angle = 0.5 * (2.0 * covariance).atan2(variance.x - variance.y);
+        let major_axis = Vec2::from_angle(angle);
+        let minor_axis = Vec2::new(-major_axis.y, major_axis.x);
+        let eccentricity = if major > 0.0 {
+            (1.0 - minor / major).sqrt()
+        } else {
+            0.0
+        };
+        // A uniform ellipse with semi-axis a has variance a²/4 along that axis
+        let ellipse = Ellipse::new(
+            center,
+            Vec2::new(2.0 * major.sqrt(), 2.0 * minor.sqrt()),
+            angle,
+        );
+        PrincipalAxes {
+            eigenvalues: (major, minor),
+            eigenvectors: (major_axis, minor_axis),
+            angle,
+            eccentricity,
+            ellipse,
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use kurbo::common::GAUSS_LEGENDRE_COEFFS_16;
+use kurbo::{CubicBez, ParamCurve, ParamCurveArea, ParamCurveDeriv, PathSeg, Point, Vec2};
+
+use crate::poly::{poly_integrate_unit, poly_mul};
+use crate::{ComputeBoundaryStatistics, CurveStatistics, StatisticsError};
+
+/// The deepest that a segment is subdivided when integrating along it
+const MAX_DEPTH: usize = 16;
+
+/// Statistics of the outline of a path, weighted by arc length
+///
+/// Where [GreenStatistics](crate::GreenStatistics) treats the path as a filled
+/// region, this treats it as a uniform wire: each moment is the integral of the
+/// corresponding power of the coordinates along the curve, with respect to arc
+/// length. This is the appropriate model for stroke fonts and centerlines.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct BoundaryStatistics {
+    /// The total arc length of the path
+    pub length: f64,
+    pub moment_x: f64,
+    pub moment_y: f64,
+    pub moment_xx: f64,
+    pub moment_xy: f64,
+    pub moment_yy: f64,
+    pub moment_xxx: f64,
+    pub moment_yyy: f64,
+    pub moment_xxxx: f64,
+    pub moment_yyyy: f64,
+    area: f64,
+}
+
+impl CurveStatistics for BoundaryStatistics {
+    /// The signed area enclosed by the path, as given by [kurbo::Shape::area]
+    fn area(&self) -> f64 {
+        self.area
+    }
+
+    /// Find the centroid of the outline
+    fn center_of_mass(&self) -> Point {
+        Point::new(self.moment_x / self.length, self.moment_y / self.length)
+    }
+
+    /// Find the variance of the outline
+    fn variance(&self) -> Vec2 {
+        let mean = self.center_of_mass();
+        Vec2::new(
+            (self.moment_xx / self.length - mean.x * mean.x).abs(),
+            (self.moment_yy / self.length - mean.y * mean.y).abs(),
+        )
+    }
+
+    /// Find the covariance of the outline
+    fn covariance(&self) -> f64 {
+        let mean = self.center_of_mass();
+        self.moment_xy / self.length - mean.x * mean.y
+    }
+
+    /// Check that the outline has a finite, non-zero length
+    fn check(&self) -> Result<(), StatisticsError> {
+        if !self.length.is_finite() {
+            Err(StatisticsError::NonFinite)
+        } else if self.length == 0.0 {
+            Err(StatisticsError::DegenerateLength)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Find the skewness of the outline
+    fn skewness(&self) -> Vec2 {
+        let mean = self.center_of_mass();
+        let variance = self.variance();
+        let third = |m1: f64, m2: f64, m3: f64| {
+            m3 / self.length - 3.0 * m1 * m2 / self.length + 2.0 * m1.powi(3)
+        };
+        Vec2::new(
+            third(mean.x, self.moment_xx, self.moment_xxx) / variance.x.powf(1.5),
+            third(mean.y, self.moment_yy, self.moment_yyy) / variance.y.powf(1.5),
+        )
+    }
+
+    /// Find the (excess) kurtosis of the outline
+    fn kurtosis(&self) -> Vec2 {
+        let mean = self.center_of_mass();
+        let variance = self.variance();
+        let fourth = |m1: f64, m2: f64, m3: f64, m4: f64| {
+            m4 / self.length - 4.0 * m1 * m3 / self.length + 6.0 * m1.powi(2) * m2 / self.length
+                - 3.0 * m1.powi(4)
+        };
+        Vec2::new(
+            fourth(mean.x, self.moment_xx, self.moment_xxx, self.moment_xxxx) / variance.x.powi(2)
+                - 3.0,
+            fourth(mean.y, self.moment_yy, self.moment_yyy, self.moment_yyyy) / variance.y.powi(2)
+                - 3.0,
+        )
+    }
+}
+
+impl BoundaryStatistics {
+    fn accumulate(&mut self, moments: [f64; 10]) {
+        self.length += moments[0];
+        self.moment_x += moments[1];
+        self.moment_y += moments[2];
+        self.moment_xx += moments[3];
+        self.moment_xy += moments[4];
+        self.moment_yy += moments[5];
+        self.moment_xxx += moments[6];
+        self.moment_yyy += moments[7];
+        self.moment_xxxx += moments[8];
+        self.moment_yyyy += moments[9];
+    }
+
+    fn handle_line(&mut self, p0: Point, p1: Point) {
+        let length = (p1 - p0).hypot();
+        let x = [p0.x, p1.x - p0.x];
+        let y = [p0.y, p1.y - p0.y];
+        let xx = poly_mul(&x, &x);
+        let yy = poly_mul(&y, &y);
+        let polys = [
+            vec![1.0],
+            x.to_vec(),
+            y.to_vec(),
+            xx.clone(),
+            poly_mul(&x, &y),
+            yy.clone(),
+            poly_mul(&xx, &x),
+            poly_mul(&yy, &y),
+            poly_mul(&xx, &xx),
+            poly_mul(&yy, &yy),
+        ];
+        self.accumulate(polys.map(|poly| length * poly_integrate_unit(&poly)));
+        self.area += kurbo::Line::new(p0, p1).signed_area();
+    }
+
+    fn handle_cubic(&mut self, c: CubicBez, accuracy: f64) {
+        self.accumulate(integrate_cubic(&c, 0.0..1.0, accuracy, 0));
+        self.area += c.signed_area();
+    }
+}
+
+/// Gauss-Legendre estimate of the arc length moments of a cubic over a parameter range
+fn gauss_legendre(c: &CubicBez, range: &std::ops::Range<f64>) -> [f64; 10] {
+    let deriv = c.deriv();
+    let half = (range.end - range.start) / 2.0;
+    let mid = (range.end + range.start) / 2.0;
+    let mut moments = [0.0; 10];
+    for &(wi, xi) in GAUSS_LEGENDRE_COEFFS_16 {
+        let t = mid + half * xi;
+        let p = c.eval(t);
+        let weight = wi * half * deriv.eval(t).to_vec2().hypot();
+        let (x2, y2) = (p.x * p.x, p.y * p.y);
+        let terms = [
+            1.0,
+            p.x,
+            p.y,
+            x2,
+            p.x * p.y,
+            y2,
+            x2 * p.x,
+            y2 * p.y,
+            x2 * x2,
+            y2 * y2,
+        ];
+        for (moment, term) in moments.iter_mut().zip(terms) {
+            *moment += weight * term;
+        }
+    }
+    moments
+}
+
+/// Integrate the arc length moments of a cubic, subdividing until the length
+/// estimate converges to within `accuracy`
+fn integrate_cubic(
+    c: &CubicBez,
+    range: std::ops::Range<f64>,
+    accuracy: f64,
+    depth: usize,
+) -> [f64; 10] {
+    let whole = gauss_legendre(c, &range);
+    let mid = (range.start + range.end) / 2.0;
+    let left = gauss_legendre(c, &(range.start..mid));
+    let right = gauss_legendre(c, &(mid..range.end));
+    if depth >= MAX_DEPTH || (left[0] + right[0] - whole[0]).abs() <= accuracy {
+        let mut moments = left;
+        for (moment, r) in moments.iter_mut().zip(right) {
+            *moment += r;
+        }
+        return moments;
+    }
+    let left = integrate_cubic(c, range.start..mid, accuracy / 2.0, depth + 1);
+    let right = integrate_cubic(c, mid..range.end, accuracy / 2.0, depth + 1);
+    let mut moments = left;
+    for (moment, r) in moments.iter_mut().zip(right) {
+        *moment += r;
+    }
+    moments
+}
+
+impl<'a, T: 'a> ComputeBoundaryStatistics<'a> for T
+where
+    &'a T: IntoIterator<Item = kurbo::PathEl>,
+{
+    fn boundary_statistics(&'a self, accuracy: f64) -> BoundaryStatistics {
+        let mut statistics = BoundaryStatistics::default();
+        for seg in kurbo::segments(self) {
+            match seg {
+                PathSeg::Line(l) => statistics.handle_line(l.p0, l.p1),
+                PathSeg::Quad(q) => statistics.handle_cubic(q.raise(), accuracy),
+                PathSeg::Cubic(c) => statistics.handle_cubic(c, accuracy),
+            }
+        }
+        statistics
+    }
+}
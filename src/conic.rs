@@ -0,0 +1,92 @@
+use kurbo::{PathEl, Point, Vec2};
+
+use crate::green::Accumulator;
+use crate::{ClosePolicy, ComputeConicStatistics, GreenStatistics};
+
+/// A rational quadratic Bézier segment, i.e. a conic section
+///
+/// The weight applies to the control point; the end points have a weight of one.
+/// A weight of one gives an ordinary quadratic Bézier, a weight below one an
+/// elliptical arc, and a weight above one a hyperbolic arc. In particular, a
+/// circular arc of angle θ has a weight of `cos(θ / 2)`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ConicBez {
+    pub p0: Point,
+    pub p1: Point,
+    pub p2: Point,
+    pub weight: f64,
+}
+
+impl ConicBez {
+    /// Create a new conic segment
+    pub fn new(p0: Point, p1: Point, p2: Point, weight: f64) -> Self {
+        ConicBez { p0, p1, p2, weight }
+    }
+
+    /// Evaluate the conic at parameter `t`
+    pub fn eval(&self, t: f64) -> Point {
+        let (numerator, denominator) = self.homogeneous(t);
+        (numerator / denominator).to_point()
+    }
+
+    /// Evaluate the derivative of the conic with respect to `t`
+    pub fn deriv(&self, t: f64) -> Vec2 {
+        let (numerator, denominator) = self.homogeneous(t);
+        let mt = 1.0 - t;
+        let d_numerator = 2.0
+            * (self.weight * (mt - t) * self.p1.to_vec2() + t * self.p2.to_vec2()
+                - mt * self.p0.to_vec2());
+        let d_denominator = 2.0 * (self.weight - 1.0) * (mt - t);
+        (d_numerator * denominator - numerator * d_denominator) / (denominator * denominator)
+    }
+
+    fn homogeneous(&self, t: f64) -> (Vec2, f64) {
+        let mt = 1.0 - t;
+        let (b0, b1, b2) = (mt * mt, 2.0 * self.weight * mt * t, t * t);
+        (
+            b0 * self.p0.to_vec2() + b1 * self.p1.to_vec2() + b2 * self.p2.to_vec2(),
+            b0 + b1 + b2,
+        )
+    }
+}
+
+/// A path element which may also be a conic segment
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ConicPathEl {
+    /// An ordinary path element
+    Path(PathEl),
+    /// A conic segment from the current point, through a weighted control point
+    /// to an end point
+    ConicTo(Point, Point, f64),
+}
+
+impl From<PathEl> for ConicPathEl {
+    fn from(el: PathEl) -> Self {
+        ConicPathEl::Path(el)
+    }
+}
+
+impl<'a, T: 'a> ComputeConicStatistics<'a> for T
+where
+    &'a T: IntoIterator<Item = &'a ConicPathEl>,
+{
+    fn conic_statistics(&'a self, accuracy: f64) -> GreenStatistics {
+        let mut accumulator = Accumulator::new(ClosePolicy::Implicit);
+        for (index, el) in self.into_iter().enumerate() {
+            match *el {
+                ConicPathEl::Path(el) => {
+                    if accumulator.element(index, el).is_err() {
+                        unreachable!("implicitly closing contours cannot fail")
+                    }
+                }
+                ConicPathEl::ConicTo(p1, p2, weight) => {
+                    accumulator.conic(index, p1, p2, weight, accuracy)
+                }
+            }
+        }
+        match accumulator.finish() {
+            Ok(moments) => moments,
+            Err(_) => unreachable!("implicitly closing contours cannot fail"),
+        }
+    }
+}
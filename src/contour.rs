@@ -0,0 +1,214 @@
+use kurbo::{BezPath, PathEl, Point, Shape};
+
+use crate::fill::filled_statistics;
+use crate::{CurveStatistics, FillRule};
+
+/// The relative difference in area within which a contour counts as containing
+/// another
+const CONTAINMENT_TOLERANCE: f64 = 1e-9;
+
+/// The direction of a contour
+///
+/// Directions are given for a y-up coordinate system, as used in font outlines:
+/// counter-clockwise contours have a positive signed area.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Orientation {
+    /// The contour has a positive signed area (PostScript outer contours)
+    CounterClockwise,
+    /// The contour has a negative signed area (TrueType outer contours)
+    Clockwise,
+    /// The contour encloses no area
+    Degenerate,
+}
+
+impl Orientation {
+    /// Determine the orientation corresponding to a signed area
+    pub fn from_area(area: f64) -> Self {
+        if area > 0.0 {
+            Orientation::CounterClockwise
+        } else if area < 0.0 {
+            Orientation::Clockwise
+        } else {
+            Orientation::Degenerate
+        }
+    }
+}
+
+/// The detected direction of a contour, and its role in the outline
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ContourDirection {
+    /// The index in the path of the element which started this contour
+    pub start_index: usize,
+    /// The direction in which the contour is drawn
+    pub orientation: Orientation,
+    /// Whether the contour is a hole, i.e. the smallest contour which contains it
+    /// is not itself a hole
+    pub is_hole: bool,
+}
+
+/// What to do with a contour which does not end at its start point
+///
+/// A contour is open if it does not end at its start point: it is followed by
+/// another [PathEl::MoveTo], or by the end of the path, without a
+/// [PathEl::ClosePath] and without its last segment drawing back to the start. The
+/// moments of an open contour are unbalanced, so its contribution depends on where
+/// the origin is.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum ClosePolicy {
+    /// Close the contour with a straight line back to its start point
+    ///
+    /// This matches fontTools' `StatisticsPen`, which closes contours on `endPath`.
+    #[default]
+    Implicit,
+    /// Leave open contours out of the statistics altogether
+    IgnoreOpen,
+    /// Fail with [StatisticsError::OpenContour](crate::StatisticsError::OpenContour)
+    Error,
+}
+
+/// Statistics for a single contour (subpath) of a path
+#[derive(Debug, Clone)]
+pub struct ContourStatistics<S> {
+    /// The index in the path of the element which started this contour
+    pub start_index: usize,
+    /// Whether the contour was explicitly closed with [PathEl::ClosePath]
+    pub closed: bool,
+    /// The statistics of this contour alone
+    pub statistics: S,
+}
+
+impl<S: CurveStatistics> ContourStatistics<S> {
+    /// The signed area of the contour
+    pub fn area(&self) -> f64 {
+        self.statistics.area()
+    }
+
+    /// The direction of the contour, as determined by the sign of its area
+    pub fn orientation(&self) -> Orientation {
+        Orientation::from_area(self.statistics.area())
+    }
+}
+
+/// Statistics for each contour of a path, along with the combined total
+#[derive(Debug, Clone)]
+pub struct ContourBreakdown<S> {
+    /// One entry per contour, in path order
+    pub contours: Vec<ContourStatistics<S>>,
+    /// The statistics of the path as a whole
+    pub total: S,
+}
+
+/// A contour split out of a path
+pub(crate) struct Contour {
+    pub(crate) start_index: usize,
+    pub(crate) closed: bool,
+    pub(crate) path: BezPath,
+}
+
+impl Contour {
+    /// Whether the contour is open, in the sense described by [ClosePolicy]
+    pub(crate) fn is_open(&self) -> bool {
+        let elements = self.path.elements();
+        let start = elements.first().and_then(PathEl::end_point);
+        !self.closed && elements.last().and_then(PathEl::end_point) != start
+    }
+}
+
+/// Split a sequence of path elements into its contours
+///
+/// A new contour starts at every [PathEl::MoveTo], and wherever drawing continues
+/// after a [PathEl::ClosePath]; a repeated `ClosePath` is ignored. Elements which
+/// appear before any `MoveTo` are treated as starting from the origin.
+pub(crate) fn split_contours(elements: impl IntoIterator<Item = PathEl>) -> Vec<Contour> {
+    let mut contours: Vec<Contour> = vec![];
+    for (index, el) in elements.into_iter().enumerate() {
+        match (el, contours.last_mut()) {
+            (PathEl::MoveTo(_), _) => contours.push(Contour {
+                start_index: index,
+                closed: false,
+                path: BezPath::from_vec(vec![el]),
+            }),
+            (_, Some(contour)) if !contour.closed => {
+                contour.closed = el == PathEl::ClosePath;
+                contour.path.push(el);
+            }
+            // Closing a contour which is already closed does nothing
+            (PathEl::ClosePath, Some(_)) => {}
+            // Drawing after a ClosePath continues from the contour's start point
+            (_, Some(contour)) => {
+                let start = contour.path.elements()[0];
+                contours.push(Contour {
+                    start_index: index,
+                    closed: el == PathEl::ClosePath,
+                    path: BezPath::from_vec(vec![start, el]),
+                })
+            }
+            (_, None) => contours.push(Contour {
+                start_index: index,
+                closed: el == PathEl::ClosePath,
+                path: BezPath::from_vec(vec![PathEl::MoveTo(Point::ZERO), el]),
+            }),
+        }
+    }
+    contours
+}
+
+/// Determine which contours are holes
+///
+/// The contours form a nesting tree, in which the parent of each contour is the
+/// smallest contour which lies entirely around it. A contour is a hole if it has
+/// a parent which is not itself a hole. Contours which merely overlap, such as a
+/// crossbar drawn over a stem or the outlines of two overlapping glyphs, do not
+/// contain one another, and neither do identical contours; so the counter of a
+/// glyph is a hole even if it also lies inside some other outer contour.
+pub(crate) fn find_holes(contours: &[Contour]) -> Vec<bool> {
+    // Orient every contour positively, so that the filled area of two contours
+    // together is their union
+    let closed: Vec<BezPath> = contours
+        .iter()
+        .map(|contour| {
+            let mut path = contour.path.clone();
+            if !contour.closed {
+                path.close_path();
+            }
+            if path.area() < 0.0 {
+                path = path.reverse_subpaths();
+            }
+            path
+        })
+        .collect();
+    let areas: Vec<f64> = closed
+        .iter()
+        .map(|path| filled_statistics(path, FillRule::NonZero).area())
+        .collect();
+    // One contour lies inside another if adding it leaves the other's filled area unchanged
+    let contains = |outer: usize, inner: usize| {
+        let (a, b) = (closed[outer].bounding_box(), closed[inner].bounding_box());
+        if outer == inner
+            || b.x0 < a.x0
+            || b.y0 < a.y0
+            || b.x1 > a.x1
+            || b.y1 > a.y1
+            || areas[inner] >= areas[outer] * (1.0 - CONTAINMENT_TOLERANCE)
+        {
+            return false;
+        }
+        let union = filled_statistics(
+            closed[outer].iter().chain(closed[inner].iter()),
+            FillRule::NonZero,
+        );
+        union.area() <= areas[outer] * (1.0 + CONTAINMENT_TOLERANCE)
+    };
+    // A contour's parent is larger than it, so visit the largest contours first
+    let mut order: Vec<usize> = (0..contours.len()).collect();
+    order.sort_by(|a, b| areas[*b].total_cmp(&areas[*a]));
+    let mut holes = vec![false; contours.len()];
+    for (position, &inner) in order.iter().enumerate() {
+        let parent = order[..position]
+            .iter()
+            .rev()
+            .find(|outer| contains(**outer, inner));
+        holes[inner] = parent.is_some_and(|parent| !holes[*parent]);
+    }
+    holes
+}
@@ -1,64 +1,241 @@
-use crate::{ComputeControlStatistics, CurveStatistics};
+use crate::contour::{split_contours, ContourBreakdown, ContourStatistics};
+use crate::{
+    ComputeControlStatistics, ControlAccumulator, ControlWeighting, CurveStatistics,
+    GenericGreenStatistics, GreenStatistics, Scalar, StatisticsError, WeightedControlStatistics,
+};
 use itertools::Itertools;
 use kurbo::{PathEl, Point, Vec2};
 
-#[derive(Default)]
-pub struct ControlStatistics {
-    points: Vec<Point>,
-    total: Point, // A cache
+/// Statistics of the control points of a path, accumulated in the scalar type `S`
+///
+/// Most code will want [ControlStatistics], which accumulates in `f64`.
+#[derive(Debug, Default, Clone)]
+pub struct GenericControlStatistics<S> {
+    points: Vec<(S, S)>,
+    /// The index in `points` at which each contour starts
+    contour_starts: Vec<usize>,
+    total: (S, S), // A cache
 }
 
-impl CurveStatistics for ControlStatistics {
+/// Statistics of the control points of a path, accumulated in `f64`
+pub type ControlStatistics = GenericControlStatistics<f64>;
+
+impl<S: Scalar> CurveStatistics for GenericControlStatistics<S> {
+    /// Find the area of the control polygons
+    ///
+    /// Each contour's control points are treated as a closed polygon, and the
+    /// signed areas of the polygons are summed.
     fn area(&self) -> f64 {
-        if self.points.len() < 2 {
-            return 0.0;
-        }
         // Use the triangle formula
-        self.points
-            .iter()
-            .circular_tuple_windows()
-            .map(|(p0, p1)| p0.x * p1.y - p1.x * p0.y)
-            .sum::<f64>()
-            / 2.0
+        (self
+            .contours()
+            .flat_map(|contour| contour.iter().circular_tuple_windows())
+            .fold(S::default(), |sum, (p0, p1)| {
+                sum + (p0.0 * p1.1 - p1.0 * p0.1)
+            })
+            / S::from_f64(2.0))
+        .to_f64()
     }
+
     /// Find the center of mass of the path
     fn center_of_mass(&self) -> Point {
-        Point::new(
-            self.total.x / self.points.len() as f64,
-            self.total.y / self.points.len() as f64,
-        )
+        let (mean_x, mean_y) = self.mean();
+        Point::new(mean_x.to_f64(), mean_y.to_f64())
     }
 
     /// Find the variance of the path
     fn variance(&self) -> Vec2 {
-        let len = self.points.len() as f64;
-        if len <= 1.0 {
+        if self.points.len() <= 1 {
             return Vec2::ZERO;
         }
+        let len = self.len();
+        let one = S::from_f64(1.0);
 
-        let sum_squares = self.points.iter().fold(Point::ZERO, |total, p| {
-            Point::new(total.x + p.x * p.x, total.y + p.y * p.y)
-        });
+        let sum_squares = self
+            .points
+            .iter()
+            .fold((S::default(), S::default()), |total, p| {
+                (total.0 + p.0 * p.0, total.1 + p.1 * p.1)
+            });
         Vec2::new(
-            (sum_squares.x - (self.total.x * self.total.x) / len) / (len - 1.0),
-            (sum_squares.y - (self.total.y * self.total.y) / len) / (len - 1.0),
+            ((sum_squares.0 - (self.total.0 * self.total.0) / len) / (len - one)).to_f64(),
+            ((sum_squares.1 - (self.total.1 * self.total.1) / len) / (len - one)).to_f64(),
         )
     }
 
     /// Find the covariance of the path
     fn covariance(&self) -> f64 {
-        let sum_xy = self.points.iter().fold(0.0, |total, p| total + p.x * p.y);
-        let len = self.points.len() as f64;
-        (sum_xy - self.total.x * self.total.y / len) / (len - 1.0)
+        if self.points.len() <= 1 {
+            return 0.0;
+        }
+        let sum_xy = self
+            .points
+            .iter()
+            .fold(S::default(), |total, p| total + p.0 * p.1);
+        let len = self.len();
+        ((sum_xy - self.total.0 * self.total.1 / len) / (len - S::from_f64(1.0))).to_f64()
+    }
+
+    /// Check that there are control points, and that they are all finite
+    fn check(&self) -> Result<(), StatisticsError> {
+        if self.points.is_empty() {
+            Err(StatisticsError::EmptyInput)
+        } else if !(self.total.0.to_f64().is_finite() && self.total.1.to_f64().is_finite()) {
+            Err(StatisticsError::NonFinite)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Find the skewness of the control points
+    ///
+    /// Uses the population (biased) third standardized moment.
+    fn skewness(&self) -> Vec2 {
+        let (m2, m3, _) = self.central_moments();
+        Vec2::new(m3.x / m2.x.powf(1.5), m3.y / m2.y.powf(1.5))
+    }
+
+    /// Find the excess kurtosis of the control points
+    ///
+    /// Uses the population (biased) fourth standardized moment.
+    fn kurtosis(&self) -> Vec2 {
+        let (m2, _, m4) = self.central_moments();
+        Vec2::new(m4.x / m2.x.powi(2) - 3.0, m4.y / m2.y.powi(2) - 3.0)
     }
 }
 
-impl ControlStatistics {
+impl<S: Scalar> GenericControlStatistics<S> {
+    /// Create statistics for a single contour of control points
     pub fn new(points: Vec<Point>) -> Self {
-        let total = points.iter().fold(Point::ZERO, |total, p| {
-            Point::new(total.x + p.x, total.y + p.y)
-        });
-        ControlStatistics { points, total }
+        GenericControlStatistics::from_contours([points])
+    }
+
+    /// Create statistics for several contours of control points
+    pub fn from_contours(contours: impl IntoIterator<Item = Vec<Point>>) -> Self {
+        let mut points = vec![];
+        let mut contour_starts = vec![];
+        for contour in contours {
+            if contour.is_empty() {
+                continue;
+            }
+            contour_starts.push(points.len());
+            points.extend(
+                contour
+                    .into_iter()
+                    .map(|p| (S::from_f64(p.x), S::from_f64(p.y))),
+            );
+        }
+        let total = points
+            .iter()
+            .fold((S::default(), S::default()), |total, p| {
+                (total.0 + p.0, total.1 + p.1)
+            });
+        GenericControlStatistics {
+            points,
+            contour_starts,
+            total,
+        }
+    }
+
+    /// Compute statistics for the control points of a path, accumulating in the
+    /// scalar type `S`
+    ///
+    /// Each [PathEl::MoveTo] starts a new contour, as does drawing after a
+    /// [PathEl::ClosePath]. As in [kurbo], drawing after a `ClosePath` continues
+    /// from the start point of the contour just closed, and drawing before any
+    /// `MoveTo` starts from the origin, so that point begins the new contour.
+    pub fn from_path(elements: impl IntoIterator<Item = PathEl>) -> Self {
+        let mut contours: Vec<Vec<Point>> = vec![];
+        let mut closed = true;
+        let mut start = Point::ZERO;
+        for el in elements {
+            let points = match el {
+                PathEl::MoveTo(p) => {
+                    contours.push(vec![]);
+                    closed = false;
+                    start = p;
+                    vec![p]
+                }
+                PathEl::LineTo(p) => vec![p],
+                PathEl::QuadTo(p1, p2) => vec![p1, p2],
+                PathEl::CurveTo(p1, p2, p3) => vec![p1, p2, p3],
+                PathEl::ClosePath => {
+                    closed = true;
+                    continue;
+                }
+            };
+            if closed {
+                contours.push(vec![start]);
+                closed = false;
+            }
+            if let Some(contour) = contours.last_mut() {
+                contour.extend(points);
+            }
+        }
+        GenericControlStatistics::from_contours(contours)
+    }
+
+    /// Compute the area moments of the control polygons
+    ///
+    /// Each contour's control points are treated as the vertices of a filled
+    /// polygon, which is integrated exactly. Unlike the mean of the vertices, this
+    /// is a true area integral, and approximates the Green's theorem statistics of
+    /// the curve cheaply.
+    pub fn polygon_statistics(&self) -> GenericGreenStatistics<S> {
+        let mut statistics = GenericGreenStatistics::default();
+        for contour in self.contours() {
+            statistics.add_polygon(contour);
+        }
+        statistics
+    }
+
+    /// All of the control points, in path order
+    pub fn points(&self) -> &[(S, S)] {
+        &self.points
+    }
+
+    /// The control points of each contour, in path order
+    pub fn contours(&self) -> impl Iterator<Item = &[(S, S)]> + '_ {
+        let ends = self
+            .contour_starts
+            .iter()
+            .skip(1)
+            .copied()
+            .chain([self.points.len()]);
+        self.contour_starts
+            .iter()
+            .zip(ends)
+            .map(|(&start, end)| &self.points[start..end])
+    }
+
+    /// The number of points, in the scalar type
+    fn len(&self) -> S {
+        S::from_f64(self.points.len() as f64)
+    }
+
+    /// The center of mass, in the scalar type
+    fn mean(&self) -> (S, S) {
+        let len = self.len();
+        (self.total.0 / len, self.total.1 / len)
+    }
+
+    /// The second, third and fourth population central moments along each axis
+    fn central_moments(&self) -> (Vec2, Vec2, Vec2) {
+        let (mean_x, mean_y) = self.mean();
+        let len = self.len();
+        let zero = (S::default(), S::default());
+        let mut moments = (zero, zero, zero);
+        for p in &self.points {
+            let (dx, dy) = (p.0 - mean_x, p.1 - mean_y);
+            moments.0 .0 += dx.powi(2) / len;
+            moments.0 .1 += dy.powi(2) / len;
+            moments.1 .0 += dx.powi(3) / len;
+            moments.1 .1 += dy.powi(3) / len;
+            moments.2 .0 += dx.powi(4) / len;
+            moments.2 .1 += dy.powi(4) / len;
+        }
+        let vec = |(x, y): (S, S)| Vec2::new(x.to_f64(), y.to_f64());
+        (vec(moments.0), vec(moments.1), vec(moments.2))
     }
 }
 
@@ -67,30 +244,34 @@ where
     &'a T: IntoIterator<Item = PathEl>,
 {
     fn control_statistics(&'a self) -> ControlStatistics {
-        let mut statistics = ControlStatistics::default();
-        for el in self {
-            match el {
-                PathEl::MoveTo(p) => {
-                    statistics.points.push(p);
-                }
-                PathEl::LineTo(p) => {
-                    statistics.points.push(p);
-                }
-                PathEl::QuadTo(p1, p2) => {
-                    statistics.points.push(p1);
-                    statistics.points.push(p2);
-                }
-                PathEl::CurveTo(p1, p2, p3) => {
-                    statistics.points.push(p1);
-                    statistics.points.push(p2);
-                    statistics.points.push(p3);
-                }
-                PathEl::ClosePath => {}
-            }
+        ControlStatistics::from_path(self)
+    }
+
+    fn control_statistics_by_contour(&'a self) -> ContourBreakdown<ControlStatistics> {
+        let contours = split_contours(self)
+            .into_iter()
+            .map(|contour| ContourStatistics {
+                start_index: contour.start_index,
+                closed: contour.closed,
+                statistics: contour.path.control_statistics(),
+            })
+            .collect();
+        ContourBreakdown {
+            contours,
+            total: self.control_statistics(),
         }
-        statistics.total = statistics.points.iter().fold(Point::ZERO, |total, p| {
-            Point::new(total.x + p.x, total.y + p.y)
-        });
-        statistics
+    }
+
+    fn control_polygon_statistics(&'a self) -> GreenStatistics {
+        self.control_statistics().polygon_statistics()
+    }
+
+    fn weighted_control_statistics(
+        &'a self,
+        weighting: ControlWeighting,
+    ) -> WeightedControlStatistics {
+        let mut accumulator = ControlAccumulator::new(weighting);
+        accumulator.extend(self);
+        accumulator.finish()
     }
 }
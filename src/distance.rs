@@ -0,0 +1,165 @@
+use std::f64::consts::PI;
+
+use kurbo::{Affine, PathEl};
+
+use crate::{ComputeGreenStatistics, CurveStatistics, GreenStatistics, StatisticsError};
+
+/// Options controlling how two shapes are compared by [shape_distance]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DistanceOptions {
+    /// Rotate each shape so that its major principal axis lies along the x axis
+    /// before comparing
+    ///
+    /// This makes the distance independent of rotation, so a shape and a rotated
+    /// copy of it compare as equal. Of the two ways to lay the major axis along the
+    /// x axis, the one which gives a non-negative μ₃₀ is chosen. Note that this
+    /// also makes 'd' and 'p' equal, as one is the other turned half a turn; mirror
+    /// images such as 'b' and 'd' remain distinct, unless the shape has a line of
+    /// symmetry (a mirrored 'c' is a 'c' turned upside down). The alignment is
+    /// unstable for shapes which are nearly isotropic, such as 'o', whose principal
+    /// axes are not well defined.
+    pub align_axes: bool,
+    /// The highest order of moment compared, from two to four
+    ///
+    /// Moments of orders zero and one are always the same after normalization, so
+    /// they are not compared.
+    pub max_order: usize,
+}
+
+impl Default for DistanceOptions {
+    fn default() -> Self {
+        DistanceOptions {
+            align_axes: false,
+            max_order: 4,
+        }
+    }
+}
+
+/// The contribution of a single moment to a [ShapeDistance]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DistanceComponent {
+    /// The power of x in the moment
+    pub p: usize,
+    /// The power of y in the moment
+    pub q: usize,
+    /// The normalized moment η_pq of the first shape
+    pub a: f64,
+    /// The normalized moment η_pq of the second shape
+    pub b: f64,
+}
+
+impl DistanceComponent {
+    /// The difference between the moments of the two shapes
+    pub fn difference(&self) -> f64 {
+        self.a - self.b
+    }
+}
+
+/// The result of comparing two shapes with [shape_distance]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShapeDistance {
+    /// The Euclidean distance between the normalized moment vectors of the shapes;
+    /// zero for identical shapes
+    pub distance: f64,
+    /// The moments compared, in order of increasing order and, within each order,
+    /// decreasing power of x
+    pub components: Vec<DistanceComponent>,
+}
+
+impl ShapeDistance {
+    /// A similarity score between zero and one; one for identical shapes
+    pub fn similarity(&self) -> f64 {
+        1.0 / (1.0 + self.distance)
+    }
+
+    /// The component which contributes most to the distance
+    pub fn largest_component(&self) -> Option<&DistanceComponent> {
+        self.components
+            .iter()
+            .max_by(|a, b| a.difference().abs().total_cmp(&b.difference().abs()))
+    }
+}
+
+impl GreenStatistics {
+    /// Compare the shape described by these statistics with another
+    ///
+    /// Each shape is moved so that its center of mass is at the origin and scaled
+    /// to unit area (and, if requested, rotated onto its principal axes), and the
+    /// resulting moments are compared. The result is unaffected by the direction
+    /// in which either path is drawn.
+    ///
+    /// Fails with [StatisticsError::UnsupportedOrder] if `options.max_order` is
+    /// more than four, the highest order tracked.
+    pub fn distance(
+        &self,
+        other: &GreenStatistics,
+        options: &DistanceOptions,
+    ) -> Result<ShapeDistance, StatisticsError> {
+        if options.max_order > 4 {
+            return Err(StatisticsError::UnsupportedOrder {
+                order: options.max_order,
+            });
+        }
+        let a = self.normalized(options.align_axes)?.moment_table();
+        let b = other.normalized(options.align_axes)?.moment_table();
+        let mut components = vec![];
+        for order in 2..=options.max_order {
+            for q in 0..=order {
+                let p = order - q;
+                components.push(DistanceComponent {
+                    p,
+                    q,
+                    a: a[p][q],
+                    b: b[p][q],
+                });
+            }
+        }
+        let distance = components
+            .iter()
+            .map(|c| c.difference().powi(2))
+            .sum::<f64>()
+            .sqrt();
+        Ok(ShapeDistance {
+            distance,
+            components,
+        })
+    }
+
+    /// The moments of the shape about its center of mass, scaled to unit area,
+    /// divided through by the signed area
+    fn normalized(&self, align_axes: bool) -> Result<GreenStatistics, StatisticsError> {
+        self.check()?;
+        let mut affine = Affine::translate(-self.center_of_mass().to_vec2());
+        if align_axes {
+            affine = Affine::rotate(-self.principal_axes().angle) * affine;
+            // Divide through by the signed area, so the choice does not depend on
+            // the direction of the path
+            let aligned = self.transform(affine).central_moments();
+            if aligned.moment_xxx / aligned.area() < 0.0 {
+                affine = Affine::rotate(PI) * affine;
+            }
+        }
+        affine = Affine::scale(self.area().abs().sqrt().recip()) * affine;
+        let normalized = self.transform(affine);
+        let area = normalized.area();
+        let table = normalized.moment_table().map(|row| row.map(|m| m / area));
+        Ok(GreenStatistics::from_table(&table))
+    }
+}
+
+/// Compare the shapes of two paths
+///
+/// This is [GreenStatistics::distance] applied to the statistics of each path. A
+/// distance of zero means the shapes are the same up to position and size.
+pub fn shape_distance<'a, A: 'a, B: 'a>(
+    a: &'a A,
+    b: &'a B,
+    options: &DistanceOptions,
+) -> Result<ShapeDistance, StatisticsError>
+where
+    &'a A: IntoIterator<Item = PathEl>,
+    &'a B: IntoIterator<Item = PathEl>,
+{
+    a.green_statistics()
+        .distance(&b.green_statistics(), options)
+}
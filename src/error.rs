@@ -0,0 +1,54 @@
+use std::fmt;
+
+/// Errors which can occur when computing statistics
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum StatisticsError {
+    /// A contour did not end at its start point, and the close policy was
+    /// [ClosePolicy::Error](crate::ClosePolicy::Error)
+    OpenContour {
+        /// The index in the path of the element which started the contour
+        start_index: usize,
+    },
+    /// There were no points to compute statistics from
+    EmptyInput,
+    /// The path encloses no area, so there is nothing to average over
+    DegenerateArea,
+    /// The outline has no length, so there is nothing to average over
+    DegenerateLength,
+    /// The statistic divides by a variance which is zero, such as the
+    /// correlation of a shape with no height
+    DegenerateVariance,
+    /// A coordinate or result was infinite or NaN
+    NonFinite,
+    /// Moments of a higher order were requested than are supported, such as a
+    /// [DistanceOptions::max_order](crate::DistanceOptions::max_order) above four,
+    /// or orthogonal moments above
+    /// [MAX_ORTHOGONAL_ORDER](crate::MAX_ORTHOGONAL_ORDER)
+    UnsupportedOrder {
+        /// The order requested
+        order: usize,
+    },
+}
+
+impl fmt::Display for StatisticsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatisticsError::OpenContour { start_index } => write!(
+                f,
+                "contour starting at element {} is not closed",
+                start_index
+            ),
+            StatisticsError::EmptyInput => write!(f, "there are no points"),
+            StatisticsError::DegenerateArea => write!(f, "the path encloses no area"),
+            StatisticsError::DegenerateLength => write!(f, "the outline has no length"),
+            StatisticsError::DegenerateVariance => write!(f, "the variance is zero"),
+            StatisticsError::NonFinite => write!(f, "a value is infinite or NaN"),
+            StatisticsError::UnsupportedOrder { order } => {
+                write!(f, "moments of order {} are not supported", order)
+            }
+        }
+    }
+}
+
+impl std::error::Error for StatisticsError {}
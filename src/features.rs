@@ -0,0 +1,189 @@
+use crate::{BoundaryStatistics, CurveStatistics, GreenStatistics};
+
+/// The version of the [FeatureVector] layout
+///
+/// This is increased whenever the meaning or position of any feature changes, so
+/// stored vectors can be checked against the layout which produced them.
+pub const FEATURE_VERSION: u32 = 1;
+
+/// The number of features in a [FeatureVector]
+pub const FEATURE_COUNT: usize = 24;
+
+/// The name of each feature, in layout order
+///
+/// Suitable for use as column headings when exporting vectors.
+pub const FEATURE_NAMES: [&str; FEATURE_COUNT] = [
+    "area",
+    "center_of_mass_x",
+    "center_of_mass_y",
+    "variance_x",
+    "variance_y",
+    "covariance",
+    "correlation",
+    "slant",
+    "skewness_x",
+    "skewness_y",
+    "kurtosis_x",
+    "kurtosis_y",
+    "hu_1",
+    "hu_2",
+    "hu_3",
+    "hu_4",
+    "hu_5",
+    "hu_6",
+    "hu_7",
+    "boundary_length",
+    "boundary_variance_x",
+    "boundary_variance_y",
+    "boundary_covariance",
+    "contour_count",
+];
+
+/// How the features measured in units of length are scaled
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub enum FeatureNormalization {
+    /// Leave features in font units
+    #[default]
+    None,
+    /// Divide lengths by the given units per em, so that features are measured in ems
+    Em(f64),
+    /// Divide lengths by the shape's own standard deviation, the square root of the
+    /// mean of its variances along each axis
+    ///
+    /// The variances of the shape then average to one, so shapes of different
+    /// sizes can be compared directly.
+    UnitVariance,
+}
+
+/// A builder for a fixed-length vector of features describing a shape
+///
+/// The layout of the vector is versioned by [FEATURE_VERSION], and is:
+///
+/// | Index | Feature | Source |
+/// |-------|---------|--------|
+/// | 0 | Signed area | [CurveStatistics::area] |
+/// | 1–2 | Center of mass x, y | [CurveStatistics::center_of_mass] |
+/// | 3–4 | Variance x, y | [CurveStatistics::variance] |
+/// | 5 | Covariance | [CurveStatistics::covariance] |
+/// | 6 | Correlation | [CurveStatistics::correlation] |
+/// | 7 | Slant | [CurveStatistics::slant] |
+/// | 8–9 | Skewness x, y | [CurveStatistics::skewness] |
+/// | 10–11 | Excess kurtosis x, y | [CurveStatistics::kurtosis] |
+/// | 12–18 | Hu invariants 1–7 | [GreenStatistics::hu_invariants], if given |
+/// | 19 | Outline length | [BoundaryStatistics], if given |
+/// | 20–21 | Outline variance x, y | [BoundaryStatistics], if given |
+/// | 22 | Outline covariance | [BoundaryStatistics], if given |
+/// | 23 | Number of contours | If given |
+///
+/// Features whose data was not supplied are NaN. [FEATURE_NAMES] gives a name for
+/// each position.
+///
+/// ```
+/// use greencurves::{ComputeGreenStatistics, FeatureNormalization, FeatureVector};
+/// use kurbo::BezPath;
+/// use approx::assert_relative_eq;
+///
+/// let path = BezPath::from_svg("M0 0H500V700H0Z").unwrap();
+/// let statistics = path.green_statistics();
+/// let features = FeatureVector::new(&statistics)
+///     .hu_invariants(&statistics)
+///     .contour_count(1)
+///     .normalization(FeatureNormalization::Em(1000.0))
+///     .build();
+/// assert_relative_eq!(features[0], 0.35, epsilon = 1e-12);
+/// assert!(features[19].is_nan());
+/// ```
+#[derive(Debug, Clone)]
+pub struct FeatureVector<'a, S: ?Sized> {
+    statistics: &'a S,
+    hu_invariants: Option<[f64; 7]>,
+    boundary: Option<&'a BoundaryStatistics>,
+    contour_count: Option<usize>,
+    normalization: FeatureNormalization,
+}
+
+impl<'a, S: CurveStatistics + ?Sized> FeatureVector<'a, S> {
+    /// Start building a feature vector from the statistics of a shape
+    pub fn new(statistics: &'a S) -> Self {
+        FeatureVector {
+            statistics,
+            hu_invariants: None,
+            boundary: None,
+            contour_count: None,
+            normalization: FeatureNormalization::None,
+        }
+    }
+
+    /// Include the Hu invariants of the shape, computed from its higher-order moments
+    pub fn hu_invariants(mut self, statistics: &GreenStatistics) -> Self {
+        self.hu_invariants = Some(statistics.hu_invariants());
+        self
+    }
+
+    /// Include the length and spread of the shape's outline
+    pub fn boundary(mut self, boundary: &'a BoundaryStatistics) -> Self {
+        self.boundary = Some(boundary);
+        self
+    }
+
+    /// Include the number of contours in the shape
+    pub fn contour_count(mut self, count: usize) -> Self {
+        self.contour_count = Some(count);
+        self
+    }
+
+    /// Choose how features measured in units of length are scaled
+    pub fn normalization(mut self, normalization: FeatureNormalization) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    /// Produce the feature vector
+    ///
+    /// Correlation, slant, skewness, kurtosis and the Hu invariants have no units,
+    /// so they are unaffected by the normalization.
+    pub fn build(&self) -> [f64; FEATURE_COUNT] {
+        let statistics = self.statistics;
+        let variance = statistics.variance();
+        let scale = match self.normalization {
+            FeatureNormalization::None => 1.0,
+            FeatureNormalization::Em(units_per_em) => units_per_em.recip(),
+            FeatureNormalization::UnitVariance => ((variance.x + variance.y) / 2.0).sqrt().recip(),
+        };
+        let squared = scale * scale;
+        let center = statistics.center_of_mass();
+        let skewness = statistics.skewness();
+        let kurtosis = statistics.kurtosis();
+        let mut features = [f64::NAN; FEATURE_COUNT];
+        features[..12].copy_from_slice(&[
+            statistics.area() * squared,
+            center.x * scale,
+            center.y * scale,
+            variance.x * squared,
+            variance.y * squared,
+            statistics.covariance() * squared,
+            statistics.correlation(),
+            statistics.slant(),
+            skewness.x,
+            skewness.y,
+            kurtosis.x,
+            kurtosis.y,
+        ]);
+        if let Some(hu) = self.hu_invariants {
+            features[12..19].copy_from_slice(&hu);
+        }
+        if let Some(boundary) = self.boundary {
+            let variance = boundary.variance();
+            features[19..23].copy_from_slice(&[
+                boundary.length * scale,
+                variance.x * squared,
+                variance.y * squared,
+                boundary.covariance() * squared,
+            ]);
+        }
+        if let Some(count) = self.contour_count {
+            features[23] = count as f64;
+        }
+        features
+    }
+}
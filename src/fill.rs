@@ -0,0 +1,400 @@
+use kurbo::{
+    flatten, BezPath, Line, ParamCurve, ParamCurveDeriv, ParamCurveNearest, PathEl, PathSeg, Point,
+    Rect, Shape,
+};
+
+use crate::contour::split_contours;
+use crate::GreenStatistics;
+
+/// The rule used to decide which regions of a path are filled
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum FillRule {
+    /// A point is inside the path if its winding number is not zero
+    #[default]
+    NonZero,
+    /// A point is inside the path if its winding number is odd
+    EvenOdd,
+}
+
+impl FillRule {
+    fn is_filled(&self, winding: i32) -> bool {
+        match self {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        }
+    }
+}
+
+/// Parameters closer than this to the end of a segment are not split points
+const END_TOLERANCE: f64 = 1e-9;
+/// Subdivision stops once both parameter ranges are narrower than this
+const PARAM_TOLERANCE: f64 = 1e-12;
+/// The most box pairs examined when intersecting two curves
+const MAX_PAIRS: usize = 10_000;
+/// The tolerance for flattening the path for winding tests, relative to its size
+const FLATTEN_TOLERANCE: f64 = 1e-6;
+/// The distance either side of a segment at which to test the winding number,
+/// relative to the size of the path
+const SAMPLE_OFFSET: f64 = 1e-5;
+/// The distance within which points are considered to coincide, relative to the
+/// size of the path
+const COINCIDENT_TOLERANCE: f64 = 1e-9;
+
+/// Compute statistics for the region filled by a path under a fill rule
+///
+/// Segments are split wherever they intersect one another (or, for cubics,
+/// themselves), and wherever one ends on another which it overlaps, such as a
+/// shared stem edge. Where several pieces coincide, only one is kept. Each piece is
+/// then classified by testing the winding number just to either side of it: pieces
+/// with filled space on exactly one side form the boundary of the filled region,
+/// and are integrated in the direction which keeps the filled region on their left.
+/// Pieces too short to test reliably, such as those where two curves touch, follow
+/// their neighbours along the same segment.
+/// The result therefore always has a positive area.
+pub(crate) fn filled_statistics(
+    elements: impl IntoIterator<Item = PathEl>,
+    fill_rule: FillRule,
+) -> GreenStatistics {
+    // Close every contour so that winding numbers are well defined
+    let mut path = BezPath::new();
+    for contour in split_contours(elements) {
+        path.extend(contour.path);
+        if !contour.closed {
+            path.close_path();
+        }
+    }
+    let segments: Vec<PathSeg> = path.segments().collect();
+    let bbox = path.bounding_box();
+    let scale = bbox.width().max(bbox.height()).max(1.0);
+    // Winding numbers are taken against a polygon which lies well within the
+    // sampling offset of the true curve, as testing points very close to a curve
+    // directly is numerically fragile.
+    let polygon = Polygon::new(&path, FLATTEN_TOLERANCE * scale);
+    let offset = SAMPLE_OFFSET * scale;
+    let tolerance = COINCIDENT_TOLERANCE * scale;
+
+    let mut splits: Vec<Vec<f64>> = vec![vec![]; segments.len()];
+    for i in 0..segments.len() {
+        if let PathSeg::Cubic(_) = segments[i] {
+            for (t0, t1) in self_intersections(segments[i], tolerance) {
+                splits[i].extend([t0, t1]);
+            }
+        }
+        for j in (i + 1)..segments.len() {
+            for (ti, tj) in intersections(segments[i], segments[j], tolerance) {
+                splits[i].push(ti);
+                splits[j].push(tj);
+            }
+        }
+    }
+
+    // The pieces of each segment, in order along it
+    let mut pieces: Vec<Vec<PathSeg>> = vec![];
+    for (seg, mut ts) in segments.into_iter().zip(splits) {
+        ts.retain(|t| *t > END_TOLERANCE && *t < 1.0 - END_TOLERANCE);
+        ts.extend([0.0, 1.0]);
+        ts.sort_by(|a, b| a.total_cmp(b));
+        ts.dedup_by(|a, b| (*a - *b).abs() < END_TOLERANCE);
+        let mut kept = vec![];
+        for range in ts.windows(2) {
+            let piece = seg.subsegment(range[0]..range[1]);
+            // The winding test already accounts for every copy of a coincident
+            // piece, so each copy after the first would count the edge again
+            if !pieces
+                .iter()
+                .flatten()
+                .chain(&kept)
+                .any(|other| coincident(&piece, other, tolerance))
+            {
+                kept.push(piece);
+            }
+        }
+        pieces.push(kept);
+    }
+
+    let classify = |piece: &PathSeg| boundary_direction(piece, &polygon, fill_rule, offset);
+    let mut statistics = GreenStatistics::default();
+    for pieces in pieces {
+        // The sides of a piece shorter than the sampling offset may both lie near
+        // some other curve, as where two curves touch, so it takes its direction
+        // from the nearest longer piece of the same segment instead
+        let directions: Vec<Option<Direction>> = pieces
+            .iter()
+            .map(|piece| {
+                let size = piece.bounding_box().size();
+                (size.width.hypot(size.height) >= offset).then(|| classify(piece))
+            })
+            .collect();
+        for (i, piece) in pieces.iter().enumerate() {
+            let direction = directions[i]
+                .or_else(|| {
+                    (1..pieces.len())
+                        .flat_map(|distance| [i.checked_sub(distance), Some(i + distance)])
+                        .flatten()
+                        .find_map(|j| directions.get(j).copied().flatten())
+                })
+                .unwrap_or_else(|| classify(piece));
+            match direction {
+                Direction::Forward => statistics.add_segment(*piece),
+                Direction::Reverse => statistics.add_segment(piece.reverse()),
+                Direction::Interior => {}
+            }
+        }
+    }
+    statistics
+}
+
+/// How a piece of a segment lies relative to the filled region
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Direction {
+    /// The piece is on the boundary, with the filled region on its left
+    Forward,
+    /// The piece is on the boundary, with the filled region on its right
+    Reverse,
+    /// The piece has filled space on both sides or on neither
+    Interior,
+}
+
+/// Classify a piece by testing the winding number just to either side of it
+///
+/// The test is made at three points along the piece and the majority taken, as
+/// a single point may lie where the piece touches another curve.
+fn boundary_direction(
+    piece: &PathSeg,
+    polygon: &Polygon,
+    fill_rule: FillRule,
+    offset: f64,
+) -> Direction {
+    let [a, b, c] = [0.5, 0.25, 0.75].map(|t| {
+        let (left, right) = sides(piece, t, offset);
+        let left = fill_rule.is_filled(polygon.winding(left));
+        let right = fill_rule.is_filled(polygon.winding(right));
+        match (left, right) {
+            (true, false) => Direction::Forward,
+            (false, true) => Direction::Reverse,
+            _ => Direction::Interior,
+        }
+    });
+    if b == c {
+        b
+    } else {
+        a
+    }
+}
+
+/// A path flattened to closed polygons
+struct Polygon(Vec<Vec<Point>>);
+
+impl Polygon {
+    fn new(path: &BezPath, tolerance: f64) -> Self {
+        let mut polygons: Vec<Vec<Point>> = vec![];
+        flatten(path, tolerance, |el| match el {
+            PathEl::MoveTo(p) => polygons.push(vec![p]),
+            PathEl::LineTo(p) => {
+                if let Some(polygon) = polygons.last_mut() {
+                    polygon.push(p)
+                }
+            }
+            _ => {}
+        });
+        Polygon(polygons)
+    }
+
+    /// The winding number of a point, counting upward crossings of a ray to the right
+    fn winding(&self, pt: Point) -> i32 {
+        let mut winding = 0;
+        for polygon in &self.0 {
+            for (i, p0) in polygon.iter().enumerate() {
+                let p1 = polygon[(i + 1) % polygon.len()];
+                let side = (p1 - *p0).cross(pt - *p0);
+                if p0.y <= pt.y && p1.y > pt.y && side > 0.0 {
+                    winding += 1;
+                } else if p0.y > pt.y && p1.y <= pt.y && side < 0.0 {
+                    winding -= 1;
+                }
+            }
+        }
+        winding
+    }
+}
+
+/// Points just to the left and right of a segment at parameter `t`
+fn sides(seg: &PathSeg, t: f64, offset: f64) -> (Point, Point) {
+    let mid = seg.eval(t);
+    let mut tangent = match seg {
+        PathSeg::Line(l) => l.p1 - l.p0,
+        PathSeg::Quad(q) => q.deriv().eval(t).to_vec2(),
+        PathSeg::Cubic(c) => c.deriv().eval(t).to_vec2(),
+    };
+    if tangent.hypot2() == 0.0 {
+        tangent = seg.end() - seg.start();
+    }
+    let normal = tangent.normalize();
+    let normal = kurbo::Vec2::new(-normal.y, normal.x) * offset;
+    (mid + normal, mid - normal)
+}
+
+/// Whether two pieces trace the same curve, in either direction
+fn coincident(a: &PathSeg, b: &PathSeg, tolerance: f64) -> bool {
+    let near = |p: Point, q: Point| (p - q).hypot() <= tolerance;
+    let ends = (near(a.start(), b.start()) && near(a.end(), b.end()))
+        || (near(a.start(), b.end()) && near(a.end(), b.start()));
+    ends && [0.25, 0.5, 0.75]
+        .iter()
+        .all(|t| b.nearest(a.eval(*t), tolerance).distance_sq <= tolerance * tolerance)
+}
+
+/// Find the parameters at which two segments cross, or at which one segment ends
+/// on the other where they overlap
+fn intersections(a: PathSeg, b: PathSeg, tolerance: f64) -> Vec<(f64, f64)> {
+    match (a, b) {
+        (PathSeg::Line(la), PathSeg::Line(lb)) => line_line(la, lb, tolerance),
+        (PathSeg::Line(la), _) => b
+            .intersect_line(la)
+            .into_iter()
+            .map(|i| (i.line_t, i.segment_t))
+            .collect(),
+        (_, PathSeg::Line(lb)) => a
+            .intersect_line(lb)
+            .into_iter()
+            .map(|i| (i.segment_t, i.line_t))
+            .collect(),
+        _ => subdivide(a, 0.0..1.0, b, 0.0..1.0, tolerance),
+    }
+}
+
+/// Find the parameter pairs at which a cubic crosses itself
+fn self_intersections(seg: PathSeg, tolerance: f64) -> Vec<(f64, f64)> {
+    // Intersect the two halves of the curve with each other, then do the same
+    // within each half for a few levels, so that loops lying entirely on one side
+    // of the midpoint are found too. The halves always meet at their shared end,
+    // which is not a crossing.
+    let mut found = vec![];
+    let mut ranges = vec![(0.0, 1.0)];
+    for _ in 0..3 {
+        let mut next = vec![];
+        for (t0, t1) in ranges {
+            let mid = (t0 + t1) / 2.0;
+            found.extend(
+                subdivide(seg, t0..mid, seg, mid..t1, tolerance)
+                    .into_iter()
+                    .filter(|(ta, tb)| {
+                        (ta - mid).abs() > END_TOLERANCE || (tb - mid).abs() > END_TOLERANCE
+                    }),
+            );
+            next.extend([(t0, mid), (mid, t1)]);
+        }
+        ranges = next;
+    }
+    found
+}
+
+fn line_line(a: Line, b: Line, tolerance: f64) -> Vec<(f64, f64)> {
+    let da = a.p1 - a.p0;
+    let db = b.p1 - b.p0;
+    let offset = b.p0 - a.p0;
+    let denom = da.cross(db);
+    if denom.abs() <= tolerance * da.hypot().max(db.hypot()) {
+        // Parallel lines only meet if they overlap, in which case each is split
+        // where the other ends
+        return overlap_ends(
+            PathSeg::Line(a),
+            0.0..1.0,
+            PathSeg::Line(b),
+            0.0..1.0,
+            tolerance,
+        );
+    }
+    let ta = offset.cross(db) / denom;
+    let tb = offset.cross(da) / denom;
+    if (0.0..=1.0).contains(&ta) && (0.0..=1.0).contains(&tb) {
+        vec![(ta, tb)]
+    } else {
+        vec![]
+    }
+}
+
+/// Find where the ends of two overlapping curves lie on each other
+///
+/// Each pair gives the parameter on `a` and on `b` of an end of one of the curves
+/// which lies on the other.
+fn overlap_ends(
+    a: PathSeg,
+    a_range: std::ops::Range<f64>,
+    b: PathSeg,
+    b_range: std::ops::Range<f64>,
+    tolerance: f64,
+) -> Vec<(f64, f64)> {
+    let (sub_a, sub_b) = (a.subsegment(a_range.clone()), b.subsegment(b_range.clone()));
+    let on = |seg: &PathSeg, range: &std::ops::Range<f64>, p: Point| {
+        let nearest = seg.nearest(p, tolerance);
+        (nearest.distance_sq <= tolerance * tolerance)
+            .then_some(range.start + nearest.t * (range.end - range.start))
+    };
+    let mut found = vec![];
+    for t in [a_range.start, a_range.end] {
+        if let Some(tb) = on(&sub_b, &b_range, a.eval(t)) {
+            found.push((t, tb));
+        }
+    }
+    for t in [b_range.start, b_range.end] {
+        if let Some(ta) = on(&sub_a, &a_range, b.eval(t)) {
+            found.push((ta, t));
+        }
+    }
+    found
+}
+
+/// Intersect two curves by recursively subdividing their bounding boxes
+///
+/// Curves which overlap along a stretch meet at infinitely many points, and
+/// exhaust the subdivision budget; for those, the ends of each curve which lie on
+/// the other are returned as well, so that the overlapping stretch is split out.
+fn subdivide(
+    a: PathSeg,
+    a_range: std::ops::Range<f64>,
+    b: PathSeg,
+    b_range: std::ops::Range<f64>,
+    tolerance: f64,
+) -> Vec<(f64, f64)> {
+    let mut found: Vec<(f64, f64)> = vec![];
+    let mut stack = vec![(a_range.clone(), b_range.clone())];
+    let mut examined = 0;
+    while let Some((ra, rb)) = stack.pop() {
+        examined += 1;
+        if examined > MAX_PAIRS {
+            found.extend(overlap_ends(a, a_range, b, b_range, tolerance));
+            break;
+        }
+        let box_a = a.subsegment(ra.clone()).bounding_box();
+        let box_b = b.subsegment(rb.clone()).bounding_box();
+        if !overlaps(box_a, box_b) {
+            continue;
+        }
+        let (wa, wb) = (ra.end - ra.start, rb.end - rb.start);
+        if wa < PARAM_TOLERANCE && wb < PARAM_TOLERANCE {
+            let t = ((ra.start + ra.end) / 2.0, (rb.start + rb.end) / 2.0);
+            if !found
+                .iter()
+                .any(|f| (f.0 - t.0).abs() < 1e-7 && (f.1 - t.1).abs() < 1e-7)
+            {
+                found.push(t);
+            }
+            continue;
+        }
+        if wa >= wb {
+            let mid = (ra.start + ra.end) / 2.0;
+            stack.push((ra.start..mid, rb.clone()));
+            stack.push((mid..ra.end, rb));
+        } else {
+            let mid = (rb.start + rb.end) / 2.0;
+            stack.push((ra.clone(), rb.start..mid));
+            stack.push((ra, mid..rb.end));
+        }
+    }
+    found
+}
+
+fn overlaps(a: Rect, b: Rect) -> bool {
+    a.x0 <= b.x1 && b.x0 <= a.x1 && a.y0 <= b.y1 && b.y0 <= a.y1
+}
@@ -0,0 +1,14 @@
+use kurbo::{flatten, BezPath, PathEl};
+
+use crate::{ComputeFlattenedStatistics, ComputeGreenStatistics, GreenStatistics};
+
+impl<'a, T: 'a> ComputeFlattenedStatistics<'a> for T
+where
+    &'a T: IntoIterator<Item = PathEl>,
+{
+    fn flattened_statistics(&'a self, tolerance: f64) -> GreenStatistics {
+        let mut polygon = BezPath::new();
+        flatten(self, tolerance, |el| polygon.push(el));
+        polygon.green_statistics()
+    }
+}
@@ -0,0 +1,293 @@
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+use kurbo::{PathEl, Point, Vec2};
+
+use crate::{GenericGreenStatistics, GreenStatistics, Scalar};
+
+/// How the statistics of a path change as one of its points moves
+///
+/// Each field is the gradient of a statistic with respect to the point, i.e. its
+/// partial derivatives with respect to the point's `x` and `y` coordinates.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PointGradient {
+    /// The index of the path element which the point belongs to
+    pub element_index: usize,
+    /// The point itself
+    pub point: Point,
+    pub area: Vec2,
+    pub center_of_mass_x: Vec2,
+    pub center_of_mass_y: Vec2,
+    pub variance_x: Vec2,
+    pub variance_y: Vec2,
+    pub covariance: Vec2,
+    /// The gradient of the slant before it is snapped to zero
+    pub slant: Vec2,
+}
+
+/// The statistics of a path, along with their gradients with respect to its points
+#[derive(Debug, Clone)]
+pub struct StatisticsGradients {
+    pub statistics: GreenStatistics,
+    /// One entry for each point in the path, in path order
+    pub points: Vec<PointGradient>,
+}
+
+/// A forward-mode dual number, carrying a value and its derivative
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+struct Dual {
+    value: f64,
+    derivative: f64,
+}
+
+impl Dual {
+    fn new(value: f64, derivative: f64) -> Self {
+        Dual { value, derivative }
+    }
+
+    fn abs(self) -> Self {
+        if self.value < 0.0 {
+            -self
+        } else {
+            self
+        }
+    }
+}
+
+impl Add for Dual {
+    type Output = Dual;
+
+    fn add(self, rhs: Dual) -> Dual {
+        Dual::new(self.value + rhs.value, self.derivative + rhs.derivative)
+    }
+}
+
+impl Sub for Dual {
+    type Output = Dual;
+
+    fn sub(self, rhs: Dual) -> Dual {
+        Dual::new(self.value - rhs.value, self.derivative - rhs.derivative)
+    }
+}
+
+impl Mul for Dual {
+    type Output = Dual;
+
+    fn mul(self, rhs: Dual) -> Dual {
+        Dual::new(
+            self.value * rhs.value,
+            self.derivative * rhs.value + self.value * rhs.derivative,
+        )
+    }
+}
+
+impl Div for Dual {
+    type Output = Dual;
+
+    fn div(self, rhs: Dual) -> Dual {
+        Dual::new(
+            self.value / rhs.value,
+            (self.derivative * rhs.value - self.value * rhs.derivative) / (rhs.value * rhs.value),
+        )
+    }
+}
+
+impl Neg for Dual {
+    type Output = Dual;
+
+    fn neg(self) -> Dual {
+        Dual::new(-self.value, -self.derivative)
+    }
+}
+
+impl AddAssign for Dual {
+    fn add_assign(&mut self, rhs: Dual) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Dual {
+    fn sub_assign(&mut self, rhs: Dual) {
+        *self = *self - rhs;
+    }
+}
+
+impl Scalar for Dual {
+    fn from_f64(value: f64) -> Self {
+        Dual::new(value, 0.0)
+    }
+
+    fn to_f64(self) -> f64 {
+        self.value
+    }
+
+    fn powi(self, n: i32) -> Self {
+        if n == 0 {
+            return Dual::new(1.0, 0.0);
+        }
+        Dual::new(
+            self.value.powi(n),
+            n as f64 * self.value.powi(n - 1) * self.derivative,
+        )
+    }
+}
+
+/// The points of a path, and the segments between them
+///
+/// Contours are closed implicitly, as in
+/// [ComputeGreenStatistics::green_statistics](crate::ComputeGreenStatistics::green_statistics).
+struct Segments {
+    points: Vec<Point>,
+    /// The element index of each point, or `None` for the origin which starts a
+    /// path without an initial [PathEl::MoveTo]
+    element_indices: Vec<Option<usize>>,
+    /// The indices into `points` of each segment's control points
+    segments: Vec<Vec<usize>>,
+}
+
+impl Segments {
+    fn new(elements: impl IntoIterator<Item = PathEl>) -> Self {
+        let mut walk = Segments {
+            points: vec![Point::ZERO],
+            element_indices: vec![None],
+            segments: vec![],
+        };
+        let (mut start, mut cur) = (0, 0);
+        for (index, el) in elements.into_iter().enumerate() {
+            match el {
+                PathEl::MoveTo(p) => {
+                    walk.close(cur, start);
+                    start = walk.push(p, index);
+                    cur = start;
+                }
+                PathEl::LineTo(p) => {
+                    let p = walk.push(p, index);
+                    walk.segments.push(vec![cur, p]);
+                    cur = p;
+                }
+                PathEl::QuadTo(p1, p2) => {
+                    let (p1, p2) = (walk.push(p1, index), walk.push(p2, index));
+                    walk.segments.push(vec![cur, p1, p2]);
+                    cur = p2;
+                }
+                PathEl::CurveTo(p1, p2, p3) => {
+                    let (p1, p2, p3) = (
+                        walk.push(p1, index),
+                        walk.push(p2, index),
+                        walk.push(p3, index),
+                    );
+                    walk.segments.push(vec![cur, p1, p2, p3]);
+                    cur = p3;
+                }
+                PathEl::ClosePath => {
+                    walk.close(cur, start);
+                    cur = start;
+                }
+            }
+        }
+        walk.close(cur, start);
+        walk
+    }
+
+    fn push(&mut self, p: Point, index: usize) -> usize {
+        self.points.push(p);
+        self.element_indices.push(Some(index));
+        self.points.len() - 1
+    }
+
+    /// Add the segment closing a contour
+    ///
+    /// The closing line is needed whenever the contour has moved on from its start
+    /// point, even if it has returned to the same position: the line then has no
+    /// length, but moving either of its ends gives it some.
+    fn close(&mut self, cur: usize, start: usize) {
+        if cur != start {
+            self.segments.push(vec![cur, start]);
+        }
+    }
+}
+
+/// Add the moments of a segment whose control points are given as coordinate pairs
+fn add_segment<S: Scalar>(statistics: &mut GenericGreenStatistics<S>, points: &[(S, S)]) {
+    match points {
+        [p0, p1] => statistics.handle_line(*p0, *p1),
+        [p0, p1, p2] => statistics.handle_quad(*p0, *p1, *p2),
+        [p0, p1, p2, p3] => statistics.handle_cubic(*p0, *p1, *p2, *p3),
+        _ => unreachable!("segments have two to four points"),
+    }
+}
+
+/// Compute the statistics of a path and their gradients
+///
+/// The moments are sums over segments, so the derivative of a moment with respect
+/// to a point only involves the segments using that point. These are found by
+/// running the segment formulas on dual numbers, and the chain rule then gives
+/// the derivatives of the statistics derived from the moments.
+pub(crate) fn statistics_gradients(
+    elements: impl IntoIterator<Item = PathEl>,
+) -> StatisticsGradients {
+    let walk = Segments::new(elements);
+    let mut statistics = GreenStatistics::default();
+    // The derivatives of every moment with respect to each coordinate of each point
+    let mut derivatives = vec![[GreenStatistics::default(); 2]; walk.points.len()];
+    for segment in &walk.segments {
+        let coords: Vec<(f64, f64)> = segment
+            .iter()
+            .map(|&i| (walk.points[i].x, walk.points[i].y))
+            .collect();
+        add_segment(&mut statistics, &coords);
+        for &var in segment {
+            for (axis, derivative) in derivatives[var].iter_mut().enumerate() {
+                let seeded: Vec<(Dual, Dual)> = segment
+                    .iter()
+                    .zip(&coords)
+                    .map(|(&i, &(x, y))| {
+                        let seed = |a| if i == var && axis == a { 1.0 } else { 0.0 };
+                        (Dual::new(x, seed(0)), Dual::new(y, seed(1)))
+                    })
+                    .collect();
+                let mut dual = GenericGreenStatistics::<Dual>::default();
+                add_segment(&mut dual, &seeded);
+                *derivative += GreenStatistics::from_table(
+                    &dual
+                        .moment_table()
+                        .map(|row| row.map(|moment| moment.derivative)),
+                );
+            }
+        }
+    }
+
+    let values = statistics.moment_table();
+    let points = walk
+        .points
+        .iter()
+        .zip(&walk.element_indices)
+        .zip(&derivatives)
+        .filter_map(|((&point, &element_index), derivatives)| {
+            let [dx, dy] = derivatives.map(|d| {
+                let d = d.moment_table();
+                let moment = |i: usize, j: usize| Dual::new(values[i][j], d[i][j]);
+                let area = moment(0, 0);
+                let (cx, cy) = (moment(1, 0) / area, moment(0, 1) / area);
+                let variance_x = (moment(2, 0) / area - cx * cx).abs();
+                let variance_y = (moment(0, 2) / area - cy * cy).abs();
+                let covariance = moment(1, 1) / area - cx * cy;
+                let slant = covariance / variance_y;
+                [area, cx, cy, variance_x, variance_y, covariance, slant]
+                    .map(|dual| dual.derivative)
+            });
+            let gradient = |k: usize| Vec2::new(dx[k], dy[k]);
+            Some(PointGradient {
+                element_index: element_index?,
+                point,
+                area: gradient(0),
+                center_of_mass_x: gradient(1),
+                center_of_mass_y: gradient(2),
+                variance_x: gradient(3),
+                variance_y: gradient(4),
+                covariance: gradient(5),
+                slant: gradient(6),
+            })
+        })
+        .collect();
+    StatisticsGradients { statistics, points }
+}
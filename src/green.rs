@@ -1,48 +1,261 @@
-use kurbo::{PathEl, Point, Vec2};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
 
-use crate::{ComputeGreenStatistics, CurveStatistics};
+use std::f64::consts::TAU;
 
+use itertools::Itertools;
+use kurbo::common::GAUSS_LEGENDRE_COEFFS_16;
+use kurbo::{Affine, Arc, Circle, Ellipse, PathEl, PathSeg, Point, SvgArc, Vec2};
+
+use crate::conic::ConicBez;
+use crate::contour::{
+    find_holes, split_contours, ClosePolicy, ContourBreakdown, ContourDirection, ContourStatistics,
+    Orientation,
+};
+use crate::fill::{filled_statistics, FillRule};
+use crate::gradient::{statistics_gradients, StatisticsGradients};
+use crate::poly::{moment_quadrature, power_basis, segment_moments};
+use crate::{ComputeGreenStatistics, CurveStatistics, Scalar, StatisticsError};
+
+/// Area moments of a path, accumulated in the scalar type `S`
+///
+/// Most code will want [GreenStatistics], which accumulates in `f64`.
 #[derive(Debug, Default, Copy, Clone)]
-pub struct GreenStatistics {
-    pub moment_x: f64,
-    pub moment_y: f64,
-    pub moment_xx: f64,
-    pub moment_xy: f64,
-    pub moment_yy: f64,
-    area: f64,
+pub struct GenericGreenStatistics<S> {
+    pub moment_x: S,
+    pub moment_y: S,
+    pub moment_xx: S,
+    pub moment_xy: S,
+    pub moment_yy: S,
+    pub moment_xxx: S,
+    pub moment_xxy: S,
+    pub moment_xyy: S,
+    pub moment_yyy: S,
+    pub moment_xxxx: S,
+    pub moment_xxxy: S,
+    pub moment_xxyy: S,
+    pub moment_xyyy: S,
+    pub moment_yyyy: S,
+    area: S,
 }
 
-impl CurveStatistics for GreenStatistics {
+/// Area moments of a path, accumulated in `f64`
+pub type GreenStatistics = GenericGreenStatistics<f64>;
+
+impl<S: Scalar> CurveStatistics for GenericGreenStatistics<S> {
     fn area(&self) -> f64 {
-        self.area
+        self.area.to_f64()
     }
     /// Find the center of mass of the path
     ///
     /// Uses the formulae from https://en.wikipedia.org/wiki/Center_of_mass#A_continuous_volume
     fn center_of_mass(&self) -> Point {
-        Point::new(self.moment_x / self.area, self.moment_y / self.area)
+        let (mean_x, mean_y) = self.mean();
+        Point::new(mean_x.to_f64(), mean_y.to_f64())
     }
 
     /// Find the variance of the path
     fn variance(&self) -> Vec2 {
-        let mean = self.center_of_mass();
+        let (mean_x, mean_y) = self.mean();
         Vec2::new(
-            (self.moment_xx / self.area - mean.x * mean.x).abs(),
-            (self.moment_yy / self.area - mean.y * mean.y).abs(),
+            (self.moment_xx / self.area - mean_x * mean_x)
+                .to_f64()
+                .abs(),
+            (self.moment_yy / self.area - mean_y * mean_y)
+                .to_f64()
+                .abs(),
         )
     }
 
     /// Find the covariance of the path
     fn covariance(&self) -> f64 {
-        let mean = self.center_of_mass();
-        self.moment_xy / self.area - mean.x * mean.y
+        let (mean_x, mean_y) = self.mean();
+        (self.moment_xy / self.area - mean_x * mean_y).to_f64()
+    }
+
+    /// Find the skewness of the path
+    ///
+    /// This is the third standardized moment of the area along each axis.
+    fn skewness(&self) -> Vec2 {
+        let (mean_x, mean_y) = self.mean();
+        let variance = self.variance();
+        let c = S::from_f64;
+        let third = |m1: S, m2: S, m3: S| {
+            (m3 / self.area - c(3.0) * m1 * m2 / self.area + c(2.0) * m1.powi(3)).to_f64()
+        };
+        Vec2::new(
+            third(mean_x, self.moment_xx, self.moment_xxx) / variance.x.powf(1.5),
+            third(mean_y, self.moment_yy, self.moment_yyy) / variance.y.powf(1.5),
+        )
+    }
+
+    /// Find the (excess) kurtosis of the path
+    ///
+    /// This is the fourth standardized moment of the area along each axis, minus three.
+    fn kurtosis(&self) -> Vec2 {
+        let (mean_x, mean_y) = self.mean();
+        let variance = self.variance();
+        let c = S::from_f64;
+        let fourth = |m1: S, m2: S, m3: S, m4: S| {
+            (m4 / self.area - c(4.0) * m1 * m3 / self.area + c(6.0) * m1.powi(2) * m2 / self.area
+                - c(3.0) * m1.powi(4))
+            .to_f64()
+        };
+        Vec2::new(
+            fourth(mean_x, self.moment_xx, self.moment_xxx, self.moment_xxxx) / variance.x.powi(2)
+                - 3.0,
+            fourth(mean_y, self.moment_yy, self.moment_yyy, self.moment_yyyy) / variance.y.powi(2)
+                - 3.0,
+        )
     }
 }
 
-impl GreenStatistics {
-    fn handle_line(&mut self, p0: Point, p1: Point) {
-        let (x0, y0) = (p0.x, p0.y);
-        let (x1, y1) = (p1.x, p1.y);
+/// The highest order of moment tracked by [GreenStatistics]
+const MAX_ORDER: usize = 4;
+
+/// Moments indexed by the powers of x and y; entry `[0][0]` is the area
+pub(crate) type MomentTable<S = f64> = [[S; MAX_ORDER + 1]; MAX_ORDER + 1];
+
+impl<S: Scalar> GenericGreenStatistics<S> {
+    /// Create statistics from raw area and moments
+    ///
+    /// The moments are the integrals of `x`, `y`, `x²`, `xy` and `y²` over the
+    /// (signed) area, as accumulated by [ComputeGreenStatistics::green_statistics].
+    /// The higher-order moments start at zero; set the corresponding public
+    /// fields if they are known.
+    pub fn from_moments(
+        area: S,
+        moment_x: S,
+        moment_y: S,
+        moment_xx: S,
+        moment_xy: S,
+        moment_yy: S,
+    ) -> Self {
+        GenericGreenStatistics {
+            area,
+            moment_x,
+            moment_y,
+            moment_xx,
+            moment_xy,
+            moment_yy,
+            ..Default::default()
+        }
+    }
+
+    /// Compute statistics for a path, accumulating in the scalar type `S`
+    ///
+    /// Contours are closed implicitly, as in [ComputeGreenStatistics::green_statistics].
+    pub fn from_path(elements: impl IntoIterator<Item = PathEl>) -> Self {
+        let mut accumulator = Accumulator::new(ClosePolicy::Implicit);
+        let result = elements
+            .into_iter()
+            .enumerate()
+            .try_for_each(|(index, el)| accumulator.element(index, el))
+            .and_then(|_| accumulator.finish());
+        match result {
+            Ok(moments) => moments,
+            Err(_) => unreachable!("implicitly closing contours cannot fail"),
+        }
+    }
+
+    /// Apply an affine transformation to the statistics
+    ///
+    /// This gives the same result as computing the statistics of the transformed
+    /// path, but maps the moments in closed form rather than re-integrating the
+    /// segments. A transformation with a negative determinant (a reflection)
+    /// reverses the sign of the area, just as it reverses the path's direction.
+    pub fn transform(&self, affine: Affine) -> Self {
+        let [a, b, c, d, e, f] = affine.as_coeffs().map(S::from_f64);
+        let det = S::from_f64(affine.determinant());
+        let table = self.moment_table();
+        let mut transformed = zero_table();
+        // (a x + c y + e)^p, as a polynomial in x and y
+        let mut x_power = zero_table();
+        x_power[0][0] = S::from_f64(1.0);
+        for (p, row) in transformed.iter_mut().enumerate() {
+            // (a x + c y + e)^p (b x + d y + f)^q
+            let mut term = x_power;
+            for moment in row.iter_mut().take(MAX_ORDER + 1 - p) {
+                *moment = det
+                    * (0..=MAX_ORDER)
+                        .flat_map(|i| (0..=(MAX_ORDER - i)).map(move |j| (i, j)))
+                        .map(|(i, j)| term[i][j] * table[i][j])
+                        .fold(S::default(), |sum, term| sum + term);
+                term = mul_linear(&term, b, d, f);
+            }
+            x_power = mul_linear(&x_power, a, c, e);
+        }
+        Self::from_table(&transformed)
+    }
+
+    /// Combine two sets of statistics moment by moment
+    fn zip_with(&self, other: &Self, f: impl Fn(S, S) -> S) -> Self {
+        let (a, b) = (self.moment_table(), other.moment_table());
+        let mut table = zero_table();
+        for i in 0..=MAX_ORDER {
+            for j in 0..=(MAX_ORDER - i) {
+                table[i][j] = f(a[i][j], b[i][j]);
+            }
+        }
+        Self::from_table(&table)
+    }
+
+    pub(crate) fn moment_table(&self) -> MomentTable<S> {
+        let mut table = zero_table();
+        table[0][0] = self.area;
+        table[1][0] = self.moment_x;
+        table[0][1] = self.moment_y;
+        table[2][0] = self.moment_xx;
+        table[1][1] = self.moment_xy;
+        table[0][2] = self.moment_yy;
+        table[3][0] = self.moment_xxx;
+        table[2][1] = self.moment_xxy;
+        table[1][2] = self.moment_xyy;
+        table[0][3] = self.moment_yyy;
+        table[4][0] = self.moment_xxxx;
+        table[3][1] = self.moment_xxxy;
+        table[2][2] = self.moment_xxyy;
+        table[1][3] = self.moment_xyyy;
+        table[0][4] = self.moment_yyyy;
+        table
+    }
+
+    pub(crate) fn from_table(table: &MomentTable<S>) -> Self {
+        GenericGreenStatistics {
+            area: table[0][0],
+            moment_x: table[1][0],
+            moment_y: table[0][1],
+            moment_xx: table[2][0],
+            moment_xy: table[1][1],
+            moment_yy: table[0][2],
+            moment_xxx: table[3][0],
+            moment_xxy: table[2][1],
+            moment_xyy: table[1][2],
+            moment_yyy: table[0][3],
+            moment_xxxx: table[4][0],
+            moment_xxxy: table[3][1],
+            moment_xxyy: table[2][2],
+            moment_xyyy: table[1][3],
+            moment_yyyy: table[0][4],
+        }
+    }
+
+    /// Add the area moments of a closed polygon
+    pub(crate) fn add_polygon(&mut self, points: &[(S, S)]) {
+        for (p0, p1) in points.iter().circular_tuple_windows() {
+            if p0 != p1 {
+                self.handle_line(*p0, *p1);
+            }
+        }
+    }
+
+    /// The center of mass, in the scalar type
+    fn mean(&self) -> (S, S) {
+        (self.moment_x / self.area, self.moment_y / self.area)
+    }
+
+    pub(crate) fn handle_line(&mut self, (x0, y0): (S, S), (x1, y1): (S, S)) {
+        let c = S::from_f64;
         let r0 = x1 * y0;
         let r1 = x1 * y1;
         let r2 = x1.powi(2);
@@ -50,127 +263,128 @@ impl GreenStatistics {
         let r4 = y0 - y1;
         let r5 = r4 * x0;
         let r6 = x0.powi(2);
-        let r7 = 2.0 * y0;
+        let r7 = c(2.0) * y0;
         let r8 = y0.powi(2);
         let r9 = y1.powi(2);
         let r10 = x1.powi(3);
         let r11 = y0.powi(3);
         let r12 = y1.powi(3);
-        self.area += -r0 / 2.0 - r1 / 2.0 + x0 * (y0 + y1) / 2.0;
-        self.moment_x += -r2 * y0 / 6.0 - r3 / 3.0 - r5 * x1 / 6.0 + r6 * (r7 + y1) / 6.0;
-        self.moment_y +=
-            -r0 * y1 / 6.0 - r8 * x1 / 6.0 - r9 * x1 / 6.0 + x0 * (r8 + r9 + y0 * y1) / 6.0;
-        self.moment_xx += -r10 * y0 / 12.0 - r10 * y1 / 4.0 - r2 * r5 / 12.0 - r4 * r6 * x1 / 12.0
-            + x0.powi(3) * (3.0 * y0 + y1) / 12.0;
-        self.moment_xy += -r2 * r8 / 24.0 - r2 * r9 / 8.0 - r3 * r7 / 24.0
-            + r6 * (r7 * y1 + 3.0 * r8 + r9) / 24.0
-            - x0 * x1 * (r8 - r9) / 12.0;
-        self.moment_yy += -r0 * r9 / 12.0 - r1 * r8 / 12.0 - r11 * x1 / 12.0 - r12 * x1 / 12.0
-            + x0 * (r11 + r12 + r8 * y1 + r9 * y0) / 12.0;
-    }
-
-    fn handle_quad(&mut self, p0: Point, p1: Point, p2: Point) {
-        let (x0, y0) = (p0.x, p0.y);
-        let x1 = p1.x;
-        let y1 = p1.y;
-        let x2 = p2.x;
-        let y2 = p2.y;
-
-        let r0 = 2.0 * y1;
+        self.area += -r0 / c(2.0) - r1 / c(2.0) + x0 * (y0 + y1) / c(2.0);
+        self.moment_x +=
+            -r2 * y0 / c(6.0) - r3 / c(3.0) - r5 * x1 / c(6.0) + r6 * (r7 + y1) / c(6.0);
+        self.moment_y += -r0 * y1 / c(6.0) - r8 * x1 / c(6.0) - r9 * x1 / c(6.0)
+            + x0 * (r8 + r9 + y0 * y1) / c(6.0);
+        self.moment_xx +=
+            -r10 * y0 / c(12.0) - r10 * y1 / c(4.0) - r2 * r5 / c(12.0) - r4 * r6 * x1 / c(12.0)
+                + x0.powi(3) * (c(3.0) * y0 + y1) / c(12.0);
+        self.moment_xy += -r2 * r8 / c(24.0) - r2 * r9 / c(8.0) - r3 * r7 / c(24.0)
+            + r6 * (r7 * y1 + c(3.0) * r8 + r9) / c(24.0)
+            - x0 * x1 * (r8 - r9) / c(12.0);
+        self.moment_yy +=
+            -r0 * r9 / c(12.0) - r1 * r8 / c(12.0) - r11 * x1 / c(12.0) - r12 * x1 / c(12.0)
+                + x0 * (r11 + r12 + r8 * y1 + r9 * y0) / c(12.0);
+        self.handle_higher_order(&[x0, x1], &[y0, y1]);
+    }
+
+    pub(crate) fn handle_quad(&mut self, (x0, y0): (S, S), (x1, y1): (S, S), (x2, y2): (S, S)) {
+        let c = S::from_f64;
+        let r0 = c(2.0) * y1;
         let r1 = r0 * x2;
         let r2 = x2 * y2;
-        let r3 = 3.0 * r2;
-        let r4 = 2.0 * x1;
-        let r5 = 3.0 * y0;
+        let r3 = c(3.0) * r2;
+        let r4 = c(2.0) * x1;
+        let r5 = c(3.0) * y0;
         let r6 = x1.powi(2);
         let r7 = x2.powi(2);
-        let r8 = 4.0 * y1;
-        let r9 = 10.0 * y2;
-        let r10 = 2.0 * y2;
+        let r8 = c(4.0) * y1;
+        let r9 = c(10.0) * y2;
+        let r10 = c(2.0) * y2;
         let r11 = r4 * x2;
         let r12 = x0.powi(2);
-        let r13 = 10.0 * y0;
+        let r13 = c(10.0) * y0;
         let r14 = r4 * y2;
         let r15 = x2 * y0;
-        let r16 = 4.0 * x1;
+        let r16 = c(4.0) * x1;
         let r17 = r0 * x1 + r2;
         let r18 = r2 * r8;
         let r19 = y1.powi(2);
-        let r20 = 2.0 * r19;
+        let r20 = c(2.0) * r19;
         let r21 = y2.powi(2);
         let r22 = r21 * x2;
-        let r23 = 5.0 * r22;
+        let r23 = c(5.0) * r22;
         let r24 = y0.powi(2);
         let r25 = y0 * y2;
-        let r26 = 5.0 * r24;
+        let r26 = c(5.0) * r24;
         let r27 = x1.powi(3);
         let r28 = x2.powi(3);
-        let r29 = 30.0 * y1;
-        let r30 = 6.0 * y1;
-        let r31 = 10.0 * r7 * x1;
-        let r32 = 5.0 * y2;
-        let r33 = 12.0 * r6;
-        let r34 = 30.0 * x1;
+        let r29 = c(30.0) * y1;
+        let r30 = c(6.0) * y1;
+        let r31 = c(10.0) * r7 * x1;
+        let r32 = c(5.0) * y2;
+        let r33 = c(12.0) * r6;
+        let r34 = c(30.0) * x1;
         let r35 = x1 * y1;
-        let r36 = r3 + 20.0 * r35;
-        let r37 = 12.0 * x1;
-        let r38 = 20.0 * r6;
-        let r39 = 8.0 * r6 * y1;
+        let r36 = r3 + c(20.0) * r35;
+        let r37 = c(12.0) * x1;
+        let r38 = c(20.0) * r6;
+        let r39 = c(8.0) * r6 * y1;
         let r40 = r32 * r7;
-        let r41 = 60.0 * y1;
-        let r42 = 20.0 * r19;
-        let r43 = 4.0 * r19;
-        let r44 = 15.0 * r21;
-        let r45 = 12.0 * x2;
-        let r46 = 12.0 * y2;
-        let r47 = 6.0 * x1;
-        let r48 = 8.0 * r19 * x1 + r23;
-        let r49 = 8.0 * y1.powi(3);
+        let r41 = c(60.0) * y1;
+        let r42 = c(20.0) * r19;
+        let r43 = c(4.0) * r19;
+        let r44 = c(15.0) * r21;
+        let r45 = c(12.0) * x2;
+        let r46 = c(12.0) * y2;
+        let r47 = c(6.0) * x1;
+        let r48 = c(8.0) * r19 * x1 + r23;
+        let r49 = c(8.0) * y1.powi(3);
         let r50 = y2.powi(3);
         let r51 = y0.powi(3);
-        let r52 = 10.0 * y1;
-        let r53 = 12.0 * y1;
-        self.area +=
-            -r1 / 6.0 - r3 / 6.0 + x0 * (r0 + r5 + y2) / 6.0 + x1 * y2 / 3.0 - y0 * (r4 + x2) / 6.0;
-        self.moment_x += -r11 * (-r10 + y1) / 30.0 + r12 * (r13 + r8 + y2) / 30.0 + r6 * y2 / 15.0
-            - r7 * r8 / 30.0
-            - r7 * r9 / 30.0
-            + x0 * (r14 - r15 - r16 * y0 + r17) / 30.0
-            - y0 * (r11 + 2.0 * r6 + r7) / 30.0;
-        self.moment_y += -r18 / 30.0 - r20 * x2 / 30.0 - r23 / 30.0 - r24 * (r16 + x2) / 30.0
-            + x0 * (r0 * y2 + r20 + r21 + r25 + r26 + r8 * y0) / 30.0
-            + x1 * y2 * (r10 + y1) / 15.0
-            - y0 * (r1 + r17) / 30.0;
-        self.moment_xx += r12 * (r1 - 5.0 * r15 - r34 * y0 + r36 + r9 * x1) / 420.0
-            + 2.0 * r27 * y2 / 105.0
-            - r28 * r29 / 420.0
-            - r28 * y2 / 4.0
-            - r31 * (r0 - 3.0 * y2) / 420.0
-            - r6 * x2 * (r0 - r32) / 105.0
-            + x0.powi(3) * (r30 + 21.0 * y0 + y2) / 84.0
+        let r52 = c(10.0) * y1;
+        let r53 = c(12.0) * y1;
+        self.area += -r1 / c(6.0) - r3 / c(6.0) + x0 * (r0 + r5 + y2) / c(6.0) + x1 * y2 / c(3.0)
+            - y0 * (r4 + x2) / c(6.0);
+        self.moment_x +=
+            -r11 * (-r10 + y1) / c(30.0) + r12 * (r13 + r8 + y2) / c(30.0) + r6 * y2 / c(15.0)
+                - r7 * r8 / c(30.0)
+                - r7 * r9 / c(30.0)
+                + x0 * (r14 - r15 - r16 * y0 + r17) / c(30.0)
+                - y0 * (r11 + c(2.0) * r6 + r7) / c(30.0);
+        self.moment_y +=
+            -r18 / c(30.0) - r20 * x2 / c(30.0) - r23 / c(30.0) - r24 * (r16 + x2) / c(30.0)
+                + x0 * (r0 * y2 + r20 + r21 + r25 + r26 + r8 * y0) / c(30.0)
+                + x1 * y2 * (r10 + y1) / c(15.0)
+                - y0 * (r1 + r17) / c(30.0);
+        self.moment_xx += r12 * (r1 - c(5.0) * r15 - r34 * y0 + r36 + r9 * x1) / c(420.0)
+            + c(2.0) * r27 * y2 / c(105.0)
+            - r28 * r29 / c(420.0)
+            - r28 * y2 / c(4.0)
+            - r31 * (r0 - c(3.0) * y2) / c(420.0)
+            - r6 * x2 * (r0 - r32) / c(105.0)
+            + x0.powi(3) * (r30 + c(21.0) * y0 + y2) / c(84.0)
             - x0 * (r0 * r7 + r15 * r37 - r2 * r37 - r33 * y2 + r38 * y0 - r39 - r40 + r5 * r7)
-                / 420.0
-            - y0 * (8.0 * r27 + 5.0 * r28 + r31 + r33 * x2) / 420.0;
-        self.moment_xy += r12 * (r13 * y2 + 3.0 * r21 + 105.0 * r24 + r41 * y0 + r42 + r46 * y1)
-            / 840.0
-            - r16 * x2 * (r43 - r44) / 840.0
-            - r21 * r7 / 8.0
-            - r24 * (r38 + r45 * x1 + 3.0 * r7) / 840.0
-            - r41 * r7 * y2 / 840.0
-            - r42 * r7 / 840.0
-            + r6 * y2 * (r32 + r8) / 210.0
-            + x0 * (-r15 * r8 + r16 * r25 + r18 + r21 * r47 - r24 * r34 - r26 * x2
-                + r35 * r46
-                + r48)
-                / 420.0
-            - y0 * (r16 * r2 + r30 * r7 + r35 * r45 + r39 + r40) / 420.0;
-
-        self.moment_yy += -r2 * r42 / 420.0
-            - r22 * r29 / 420.0
-            - r24 * (r14 + r36 + r52 * x2) / 420.0
-            - r49 * x2 / 420.0
-            - r50 * x2 / 12.0
-            - r51 * (r47 + x2) / 84.0
+                / c(420.0)
+            - y0 * (c(8.0) * r27 + c(5.0) * r28 + r31 + r33 * x2) / c(420.0);
+        self.moment_xy +=
+            r12 * (r13 * y2 + c(3.0) * r21 + c(105.0) * r24 + r41 * y0 + r42 + r46 * y1) / c(840.0)
+                - r16 * x2 * (r43 - r44) / c(840.0)
+                - r21 * r7 / c(8.0)
+                - r24 * (r38 + r45 * x1 + c(3.0) * r7) / c(840.0)
+                - r41 * r7 * y2 / c(840.0)
+                - r42 * r7 / c(840.0)
+                + r6 * y2 * (r32 + r8) / c(210.0)
+                + x0 * (-r15 * r8 + r16 * r25 + r18 + r21 * r47 - r24 * r34 - r26 * x2
+                    + r35 * r46
+                    + r48)
+                    / c(420.0)
+                - y0 * (r16 * r2 + r30 * r7 + r35 * r45 + r39 + r40) / c(420.0);
+
+        self.moment_yy += -r2 * r42 / c(420.0)
+            - r22 * r29 / c(420.0)
+            - r24 * (r14 + r36 + r52 * x2) / c(420.0)
+            - r49 * x2 / c(420.0)
+            - r50 * x2 / c(12.0)
+            - r51 * (r47 + x2) / c(84.0)
             + x0 * (r19 * r46
                 + r21 * r5
                 + r21 * r52
@@ -179,214 +393,215 @@ impl GreenStatistics {
                 + r26 * y2
                 + r42 * y0
                 + r49
-                + 5.0 * r50
-                + 35.0 * r51)
-                / 420.0
-            + x1 * y2 * (r43 + r44 + r9 * y1) / 210.0
-            - y0 * (r19 * r45 + r2 * r53 - r21 * r4 + r48) / 420.0;
-    }
-
-    fn handle_cubic(&mut self, p0: Point, p1: Point, p2: Point, p3: Point) {
-        let x0 = p0.x;
-        let y0 = p0.y;
-        let x1 = p1.x;
-        let y1 = p1.y;
-        let x2 = p2.x;
-        let y2 = p2.y;
-        let x3 = p3.x;
-        let y3 = p3.y;
-
-        let r0 = 6.0 * y2;
+                + c(5.0) * r50
+                + c(35.0) * r51)
+                / c(420.0)
+            + x1 * y2 * (r43 + r44 + r9 * y1) / c(210.0)
+            - y0 * (r19 * r45 + r2 * r53 - r21 * r4 + r48) / c(420.0);
+        self.handle_higher_order(&[x0, x1, x2], &[y0, y1, y2]);
+    }
+
+    pub(crate) fn handle_cubic(
+        &mut self,
+        (x0, y0): (S, S),
+        (x1, y1): (S, S),
+        (x2, y2): (S, S),
+        (x3, y3): (S, S),
+    ) {
+        let c = S::from_f64;
+        let r0 = c(6.0) * y2;
         let r1 = r0 * x3;
-        let r2 = 10.0 * y3;
+        let r2 = c(10.0) * y3;
         let r3 = r2 * x3;
-        let r4 = 3.0 * y1;
-        let r5 = 6.0 * x1;
-        let r6 = 3.0 * x2;
-        let r7 = 6.0 * y1;
-        let r8 = 3.0 * y2;
+        let r4 = c(3.0) * y1;
+        let r5 = c(6.0) * x1;
+        let r6 = c(3.0) * x2;
+        let r7 = c(6.0) * y1;
+        let r8 = c(3.0) * y2;
         let r9 = x2.powi(2);
-        let r10 = 45.0 * r9;
+        let r10 = c(45.0) * r9;
         let r11 = r10 * y3;
         let r12 = x3.powi(2);
         let r13 = r12 * y2;
         let r14 = r12 * y3;
-        let r15 = 7.0 * y3;
-        let r16 = 15.0 * x3;
+        let r15 = c(7.0) * y3;
+        let r16 = c(15.0) * x3;
         let r17 = r16 * x2;
         let r18 = x1.powi(2);
-        let r19 = 9.0 * r18;
+        let r19 = c(9.0) * r18;
         let r20 = x0.powi(2);
-        let r21 = 21.0 * y1;
-        let r22 = 9.0 * r9;
+        let r21 = c(21.0) * y1;
+        let r22 = c(9.0) * r9;
         let r23 = r7 * x3;
-        let r24 = 9.0 * y2;
+        let r24 = c(9.0) * y2;
         let r25 = r24 * x2 + r3;
-        let r26 = 9.0 * x2;
+        let r26 = c(9.0) * x2;
         let r27 = x2 * y3;
-        let r28 = -r26 * y1 + 15.0 * r27;
-        let r29 = 3.0 * x1;
-        let r30 = 45.0 * x1;
-        let r31 = 12.0 * x3;
-        let r32 = 45.0 * r18;
-        let r33 = 5.0 * r12;
+        let r28 = -r26 * y1 + c(15.0) * r27;
+        let r29 = c(3.0) * x1;
+        let r30 = c(45.0) * x1;
+        let r31 = c(12.0) * x3;
+        let r32 = c(45.0) * r18;
+        let r33 = c(5.0) * r12;
         let r34 = r8 * x3;
-        let r35 = 105.0 * y0;
-        let r36 = 30.0 * y0;
+        let r35 = c(105.0) * y0;
+        let r36 = c(30.0) * y0;
         let r37 = r36 * x2;
-        let r38 = 5.0 * x3;
-        let r39 = 15.0 * y3;
-        let r40 = 5.0 * y3;
+        let r38 = c(5.0) * x3;
+        let r39 = c(15.0) * y3;
+        let r40 = c(5.0) * y3;
         let r41 = r40 * x3;
         let r42 = x2 * y2;
-        let r43 = 18.0 * r42;
-        let r44 = 45.0 * y1;
+        let r43 = c(18.0) * r42;
+        let r44 = c(45.0) * y1;
         let r45 = r41 + r43 + r44 * x1;
         let r46 = y2 * y3;
         let r47 = r46 * x3;
         let r48 = y2.powi(2);
-        let r49 = 45.0 * r48;
+        let r49 = c(45.0) * r48;
         let r50 = r49 * x3;
         let r51 = y3.powi(2);
         let r52 = r51 * x3;
         let r53 = y1.powi(2);
-        let r54 = 9.0 * r53;
+        let r54 = c(9.0) * r53;
         let r55 = y0.powi(2);
-        let r56 = 21.0 * x1;
-        let r57 = 6.0 * x2;
+        let r56 = c(21.0) * x1;
+        let r57 = c(6.0) * x2;
         let r58 = r16 * y2;
         let r59 = r39 * y2;
-        let r60 = 9.0 * r48;
+        let r60 = c(9.0) * r48;
         let r61 = r6 * y3;
-        let r62 = 3.0 * y3;
+        let r62 = c(3.0) * y3;
         let r63 = r36 * y2;
         let r64 = y1 * y3;
-        let r65 = 45.0 * r53;
-        let r66 = 5.0 * r51;
+        let r65 = c(45.0) * r53;
+        let r66 = c(5.0) * r51;
         let r67 = x2.powi(3);
         let r68 = x3.powi(3);
-        let r69 = 630.0 * y2;
-        let r70 = 126.0 * x3;
+        let r69 = c(630.0) * y2;
+        let r70 = c(126.0) * x3;
         let r71 = x1.powi(3);
-        let r72 = 126.0 * x2;
-        let r73 = 63.0 * r9;
+        let r72 = c(126.0) * x2;
+        let r73 = c(63.0) * r9;
         let r74 = r73 * x3;
-        let r75 = r15 * x3 + 15.0 * r42;
-        let r76 = 630.0 * x1;
-        let r77 = 14.0 * x3;
-        let r78 = 21.0 * r27;
-        let r79 = 42.0 * x1;
-        let r80 = 42.0 * x2;
+        let r75 = r15 * x3 + c(15.0) * r42;
+        let r76 = c(630.0) * x1;
+        let r77 = c(14.0) * x3;
+        let r78 = c(21.0) * r27;
+        let r79 = c(42.0) * x1;
+        let r80 = c(42.0) * x2;
         let r81 = x1 * y2;
-        let r82 = 63.0 * r42;
+        let r82 = c(63.0) * r42;
         let r83 = x1 * y1;
-        let r84 = r41 + r82 + 378.0 * r83;
+        let r84 = r41 + r82 + c(378.0) * r83;
         let r85 = x2 * x3;
         let r86 = r85 * y1;
         let r87 = r27 * x3;
-        let r88 = 27.0 * r9;
+        let r88 = c(27.0) * r9;
         let r89 = r88 * y2;
-        let r90 = 42.0 * r14;
-        let r91 = 90.0 * x1;
-        let r92 = 189.0 * r18;
-        let r93 = 378.0 * r18;
+        let r90 = c(42.0) * r14;
+        let r91 = c(90.0) * x1;
+        let r92 = c(189.0) * r18;
+        let r93 = c(378.0) * r18;
         let r94 = r12 * y1;
-        let r95 = 252.0 * x1 * x2;
+        let r95 = c(252.0) * x1 * x2;
         let r96 = r79 * x3;
-        let r97 = 30.0 * r85;
+        let r97 = c(30.0) * r85;
         let r98 = r83 * x3;
-        let r99 = 30.0 * x3;
-        let r100 = 42.0 * x3;
+        let r99 = c(30.0) * x3;
+        let r100 = c(42.0) * x3;
         let r101 = r42 * x1;
-        let r102 = r10 * y2 + 14.0 * r14 + 126.0 * r18 * y1 + r81 * r99;
-        let r103 = 378.0 * r48;
-        let r104 = 18.0 * y1;
+        let r102 = r10 * y2 + c(14.0) * r14 + c(126.0) * r18 * y1 + r81 * r99;
+        let r103 = c(378.0) * r48;
+        let r104 = c(18.0) * y1;
         let r105 = r104 * y2;
         let r106 = y0 * y1;
-        let r107 = 252.0 * y2;
+        let r107 = c(252.0) * y2;
         let r108 = r107 * y0;
         let r109 = y0 * y3;
-        let r110 = 42.0 * r64;
-        let r111 = 378.0 * r53;
-        let r112 = 63.0 * r48;
-        let r113 = 27.0 * x2;
+        let r110 = c(42.0) * r64;
+        let r111 = c(378.0) * r53;
+        let r112 = c(63.0) * r48;
+        let r113 = c(27.0) * x2;
         let r114 = r27 * y2;
-        let r115 = r113 * r48 + 42.0 * r52;
+        let r115 = r113 * r48 + c(42.0) * r52;
         let r116 = x3 * y3;
-        let r117 = 54.0 * r42;
+        let r117 = c(54.0) * r42;
         let r118 = r51 * x1;
         let r119 = r51 * x2;
         let r120 = r48 * x1;
-        let r121 = 21.0 * x3;
+        let r121 = c(21.0) * x3;
         let r122 = r64 * x1;
         let r123 = r81 * y3;
-        let r124 = 30.0 * r27 * y1 + r49 * x2 + 14.0 * r52 + 126.0 * r53 * x1;
+        let r124 = c(30.0) * r27 * y1 + r49 * x2 + c(14.0) * r52 + c(126.0) * r53 * x1;
         let r125 = y2.powi(3);
         let r126 = y3.powi(3);
         let r127 = y1.powi(3);
         let r128 = y0.powi(3);
         let r129 = r51 * y2;
         let r130 = r112 * y3 + r21 * r51;
-        let r131 = 189.0 * r53;
-        let r132 = 90.0 * y2;
-        self.area += -r1 / 20.0 - r3 / 20.0 - r4 * (x2 + x3) / 20.0
-            + x0 * (r7 + r8 + 10.0 * y0 + y3) / 20.0
-            + 3.0 * x1 * (y2 + y3) / 20.0
-            + 3.0 * x2 * y3 / 10.0
-            - y0 * (r5 + r6 + x3) / 20.0;
-        self.moment_x += r11 / 840.0 - r13 / 8.0 - r14 / 3.0 - r17 * (-r15 + r8) / 840.0
-            + r19 * (r8 + 2.0 * y3) / 840.0
-            + r20 * (r0 + r21 + 56.0 * y0 + y3) / 168.0
-            + r29 * (-r23 + r25 + r28) / 840.0
-            - r4 * (10.0 * r12 + r17 + r22) / 840.0
-            + x0 * (12.0 * r27 + r30 * y2 + r34 - r35 * x1 - r37 - r38 * y0 + r39 * x1 - r4 * x3
-                + r45)
-                / 840.0
-            - y0 * (r17 + r30 * x2 + r31 * x1 + r32 + r33 + 18.0 * r9) / 840.0;
-        self.moment_y += -r4 * (r25 + r58) / 840.0
-            - r47 / 8.0
-            - r50 / 840.0
-            - r52 / 6.0
-            - r54 * (r6 + 2.0 * x3) / 840.0
-            - r55 * (r56 + r57 + x3) / 168.0
+        let r131 = c(189.0) * r53;
+        let r132 = c(90.0) * y2;
+        self.area += -r1 / c(20.0) - r3 / c(20.0) - r4 * (x2 + x3) / c(20.0)
+            + x0 * (r7 + r8 + c(10.0) * y0 + y3) / c(20.0)
+            + c(3.0) * x1 * (y2 + y3) / c(20.0)
+            + c(3.0) * x2 * y3 / c(10.0)
+            - y0 * (r5 + r6 + x3) / c(20.0);
+        self.moment_x +=
+            r11 / c(840.0) - r13 / c(8.0) - r14 / c(3.0) - r17 * (-r15 + r8) / c(840.0)
+                + r19 * (r8 + c(2.0) * y3) / c(840.0)
+                + r20 * (r0 + r21 + c(56.0) * y0 + y3) / c(168.0)
+                + r29 * (-r23 + r25 + r28) / c(840.0)
+                - r4 * (c(10.0) * r12 + r17 + r22) / c(840.0)
+                + x0 * (c(12.0) * r27 + r30 * y2 + r34 - r35 * x1 - r37 - r38 * y0 + r39 * x1
+                    - r4 * x3
+                    + r45)
+                    / c(840.0)
+                - y0 * (r17 + r30 * x2 + r31 * x1 + r32 + r33 + c(18.0) * r9) / c(840.0);
+        self.moment_y += -r4 * (r25 + r58) / c(840.0)
+            - r47 / c(8.0)
+            - r50 / c(840.0)
+            - r52 / c(6.0)
+            - r54 * (r6 + c(2.0) * x3) / c(840.0)
+            - r55 * (r56 + r57 + x3) / c(168.0)
             + x0 * (r35 * y1
                 + r40 * y0
                 + r44 * y2
-                + 18.0 * r48
-                + 140.0 * r55
+                + c(18.0) * r48
+                + c(140.0) * r55
                 + r59
                 + r63
-                + 12.0 * r64
+                + c(12.0) * r64
                 + r65
                 + r66)
-                / 840.0
-            + x1 * (r24 * y1 + 10.0 * r51 + r59 + r60 + r7 * y3) / 280.0
-            + x2 * y3 * (r15 + r8) / 56.0
-            - y0 * (r16 * y1 + r31 * y2 + r44 * x2 + r45 + r61 - r62 * x1) / 840.0;
-        self.moment_xx += -r12 * r72 * (-r40 + r8) / 9240.0
-            + 3.0 * r18 * (r28 + r34 - r38 * y1 + r75) / 3080.0
+                / c(840.0)
+            + x1 * (r24 * y1 + c(10.0) * r51 + r59 + r60 + r7 * y3) / c(280.0)
+            + x2 * y3 * (r15 + r8) / c(56.0)
+            - y0 * (r16 * y1 + r31 * y2 + r44 * x2 + r45 + r61 - r62 * x1) / c(840.0);
+        self.moment_xx += -r12 * r72 * (-r40 + r8) / c(9240.0)
+            + c(3.0) * r18 * (r28 + r34 - r38 * y1 + r75) / c(3080.0)
             + r20
                 * (r24 * x3 - r72 * y0 - r76 * y0 - r77 * y0
                     + r78
                     + r79 * y3
                     + r80 * y1
-                    + 210.0 * r81
+                    + c(210.0) * r81
                     + r84)
-                / 9240.0
+                / c(9240.0)
             - r29
-                * (r12 * r21 + 14.0 * r13 + r44 * r9 - r73 * y3 + 54.0 * r86
-                    - 84.0 * r87
+                * (r12 * r21 + c(14.0) * r13 + r44 * r9 - r73 * y3 + c(54.0) * r86
+                    - c(84.0) * r87
                     - r89
                     - r90)
-                / 9240.0
-            - r4 * (70.0 * r12 * x2 + 27.0 * r67 + 42.0 * r68 + r74) / 9240.0
-            + 3.0 * r67 * y3 / 220.0
-            - r68 * r69 / 9240.0
-            - r68 * y3 / 4.0
-            - r70 * r9 * (-r62 + y2) / 9240.0
-            + 3.0 * r71 * (r24 + r40) / 3080.0
-            + x0.powi(3) * (r24 + r44 + 165.0 * y0 + y3) / 660.0
-            + x0 * (r100 * r27 + 162.0 * r101 + r102 + r11 + 63.0 * r18 * y3 + r27 * r91
+                / c(9240.0)
+            - r4 * (c(70.0) * r12 * x2 + c(27.0) * r67 + c(42.0) * r68 + r74) / c(9240.0)
+            + c(3.0) * r67 * y3 / c(220.0)
+            - r68 * r69 / c(9240.0)
+            - r68 * y3 / c(4.0)
+            - r70 * r9 * (-r62 + y2) / c(9240.0)
+            + c(3.0) * r71 * (r24 + r40) / c(3080.0)
+            + x0.powi(3) * (r24 + r44 + c(165.0) * y0 + y3) / c(660.0)
+            + x0 * (r100 * r27 + c(162.0) * r101 + r102 + r11 + c(63.0) * r18 * y3 + r27 * r91
                 - r33 * y0
                 - r37 * x3
                 + r43 * x3
@@ -394,130 +609,558 @@ impl GreenStatistics {
                 - r88 * y1
                 + r92 * y2
                 - r93 * y0
-                - 9.0 * r94
+                - c(9.0) * r94
                 - r95 * y0
                 - r96 * y0
                 - r97 * y1
-                - 18.0 * r98
+                - c(18.0) * r98
                 + r99 * x1 * y3)
-                / 9240.0
+                / c(9240.0)
             - y0 * (r12 * r56
                 + r12 * r80
                 + r32 * x3
-                + 45.0 * r67
-                + 14.0 * r68
-                + 126.0 * r71
+                + c(45.0) * r67
+                + c(14.0) * r68
+                + c(126.0) * r71
                 + r74
                 + r85 * r91
-                + 135.0 * r9 * x1
+                + c(135.0) * r9 * x1
                 + r92 * x2)
-                / 9240.0;
-        self.moment_xy += -r103 * r12 / 18480.0 - r12 * r51 / 8.0 - 3.0 * r14 * y2 / 44.0
-            + 3.0 * r18 * (r105 + r2 * y1 + 18.0 * r46 + 15.0 * r48 + 7.0 * r51) / 6160.0
-            + r20
-                * (1260.0 * r106
-                    + r107 * y1
-                    + r108
-                    + 28.0 * r109
-                    + r110
-                    + r111
-                    + r112
-                    + 30.0 * r46
-                    + 2310.0 * r55
-                    + r66)
-                / 18480.0
-            - r54 * (7.0 * r12 + 18.0 * r85 + 15.0 * r9) / 18480.0
-            - r55 * (r33 + r73 + r93 + r95 + r96 + r97) / 18480.0
-            - r7 * (42.0 * r13 + r82 * x3 + 28.0 * r87 + r89 + r90) / 18480.0
-            - 3.0 * r85 * (r48 - r66) / 220.0
-            + 3.0 * r9 * y3 * (r62 + 2.0 * y2) / 440.0
-            + x0 * (-r1 * y0 - 84.0 * r106 * x2
-                + r109 * r56
-                + 54.0 * r114
-                + r117 * y1
-                + 15.0 * r118
-                + 21.0 * r119
-                + 81.0 * r120
-                + r121 * r46
-                + 54.0 * r122
-                + 60.0 * r123
-                + r124
-                - r21 * x3 * y0
-                + r23 * y3
-                - r54 * x3
-                - r55 * r72
-                - r55 * r76
-                - r55 * r77
-                + r57 * y0 * y3
-                + r60 * x3
-                + 84.0 * r81 * y0
-                + 189.0 * r81 * y1)
-                / 9240.0
-            + x1 * (r104 * r27 - r105 * x3 - r113 * r53 + 63.0 * r114 + r115 - r16 * r53
-                + 28.0 * r47
-                + r51 * r80)
-                / 3080.0
-            - y0 * (54.0 * r101 + r102 + r116 * r5 + r117 * x3 + 21.0 * r13 - r19 * y3
-                + r22 * y3
-                + r78 * x3
-                + 189.0 * r83 * x2
-                + 60.0 * r86
-                + 81.0 * r9 * y1
-                + 15.0 * r94
-                + 54.0 * r98)
-                / 9240.0;
-        self.moment_yy += -r103 * r116 / 9240.0
-            - r125 * r70 / 9240.0
-            - r126 * x3 / 12.0
-            - 3.0 * r127 * (r26 + r38) / 3080.0
-            - r128 * (r26 + r30 + x3) / 660.0
-            - r4 * (r112 * x3 + r115 - 14.0 * r119 + 84.0 * r47) / 9240.0
-            - r52 * r69 / 9240.0
-            - r54 * (r58 + r61 + r75) / 9240.0
-            - r55 * (r100 * y1 + r121 * y2 + r26 * y3 + r79 * y2 + r84 + 210.0 * x2 * y1) / 9240.0
+                / c(9240.0);
+        self.moment_xy +=
+            -r103 * r12 / c(18480.0) - r12 * r51 / c(8.0) - c(3.0) * r14 * y2 / c(44.0)
+                + c(3.0) * r18 * (r105 + r2 * y1 + c(18.0) * r46 + c(15.0) * r48 + c(7.0) * r51)
+                    / c(6160.0)
+                + r20
+                    * (c(1260.0) * r106
+                        + r107 * y1
+                        + r108
+                        + c(28.0) * r109
+                        + r110
+                        + r111
+                        + r112
+                        + c(30.0) * r46
+                        + c(2310.0) * r55
+                        + r66)
+                    / c(18480.0)
+                - r54 * (c(7.0) * r12 + c(18.0) * r85 + c(15.0) * r9) / c(18480.0)
+                - r55 * (r33 + r73 + r93 + r95 + r96 + r97) / c(18480.0)
+                - r7 * (c(42.0) * r13 + r82 * x3 + c(28.0) * r87 + r89 + r90) / c(18480.0)
+                - c(3.0) * r85 * (r48 - r66) / c(220.0)
+                + c(3.0) * r9 * y3 * (r62 + c(2.0) * y2) / c(440.0)
+                + x0 * (-r1 * y0 - c(84.0) * r106 * x2
+                    + r109 * r56
+                    + c(54.0) * r114
+                    + r117 * y1
+                    + c(15.0) * r118
+                    + c(21.0) * r119
+                    + c(81.0) * r120
+                    + r121 * r46
+                    + c(54.0) * r122
+                    + c(60.0) * r123
+                    + r124
+                    - r21 * x3 * y0
+                    + r23 * y3
+                    - r54 * x3
+                    - r55 * r72
+                    - r55 * r76
+                    - r55 * r77
+                    + r57 * y0 * y3
+                    + r60 * x3
+                    + c(84.0) * r81 * y0
+                    + c(189.0) * r81 * y1)
+                    / c(9240.0)
+                + x1 * (r104 * r27 - r105 * x3 - r113 * r53 + c(63.0) * r114 + r115 - r16 * r53
+                    + c(28.0) * r47
+                    + r51 * r80)
+                    / c(3080.0)
+                - y0 * (c(54.0) * r101 + r102 + r116 * r5 + r117 * x3 + c(21.0) * r13 - r19 * y3
+                    + r22 * y3
+                    + r78 * x3
+                    + c(189.0) * r83 * x2
+                    + c(60.0) * r86
+                    + c(81.0) * r9 * y1
+                    + c(15.0) * r94
+                    + c(54.0) * r98)
+                    / c(9240.0);
+        self.moment_yy += -r103 * r116 / c(9240.0)
+            - r125 * r70 / c(9240.0)
+            - r126 * x3 / c(12.0)
+            - c(3.0) * r127 * (r26 + r38) / c(3080.0)
+            - r128 * (r26 + r30 + x3) / c(660.0)
+            - r4 * (r112 * x3 + r115 - c(14.0) * r119 + c(84.0) * r47) / c(9240.0)
+            - r52 * r69 / c(9240.0)
+            - r54 * (r58 + r61 + r75) / c(9240.0)
+            - r55 * (r100 * y1 + r121 * y2 + r26 * y3 + r79 * y2 + r84 + c(210.0) * x2 * y1)
+                / c(9240.0)
             + x0 * (r108 * y1
                 + r110 * y0
                 + r111 * y0
                 + r112 * y0
-                + 45.0 * r125
-                + 14.0 * r126
-                + 126.0 * r127
-                + 770.0 * r128
-                + 42.0 * r129
+                + c(45.0) * r125
+                + c(14.0) * r126
+                + c(126.0) * r127
+                + c(770.0) * r128
+                + c(42.0) * r129
                 + r130
                 + r131 * y2
                 + r132 * r64
-                + 135.0 * r48 * y1
-                + 630.0 * r55 * y1
-                + 126.0 * r55 * y2
-                + 14.0 * r55 * y3
+                + c(135.0) * r48 * y1
+                + c(630.0) * r55 * y1
+                + c(126.0) * r55 * y2
+                + c(14.0) * r55 * y3
                 + r63 * y3
                 + r65 * y3
                 + r66 * y0)
-                / 9240.0
-            + x1 * (27.0 * r125
-                + 42.0 * r126
-                + 70.0 * r129
+                / c(9240.0)
+            + x1 * (c(27.0) * r125
+                + c(42.0) * r126
+                + c(70.0) * r129
                 + r130
                 + r39 * r53
                 + r44 * r48
-                + 27.0 * r53 * y2
-                + 54.0 * r64 * y2)
-                / 3080.0
-            + 3.0 * x2 * y3 * (r48 + r66 + r8 * y3) / 220.0
-            - y0 * (r100 * r46 + 18.0 * r114
-                - 9.0 * r118
-                - 27.0 * r120
-                - 18.0 * r122
-                - 30.0 * r123
+                + c(27.0) * r53 * y2
+                + c(54.0) * r64 * y2)
+                / c(3080.0)
+            + c(3.0) * x2 * y3 * (r48 + r66 + r8 * y3) / c(220.0)
+            - y0 * (r100 * r46 + c(18.0) * r114
+                - c(9.0) * r118
+                - c(27.0) * r120
+                - c(18.0) * r122
+                - c(30.0) * r123
                 + r124
                 + r131 * x2
                 + r132 * x3 * y1
-                + 162.0 * r42 * y1
+                + c(162.0) * r42 * y1
                 + r50
-                + 63.0 * r53 * x3
+                + c(63.0) * r53 * x3
                 + r64 * r99)
-                / 9240.0;
+                / c(9240.0);
+        self.handle_higher_order(&[x0, x1, x2, x3], &[y0, y1, y2, y3]);
+    }
+
+    /// Accumulate the third- and fourth-order moments of a segment
+    ///
+    /// The segment is given by the coordinates of its control points, and
+    /// integrated exactly by [segment_moments] without allocating.
+    fn handle_higher_order(&mut self, x: &[S], y: &[S]) {
+        let (x, y) = (power_basis(x), power_basis(y));
+        let nodes = moment_quadrature(4);
+        segment_moments(&x, &y, 3..=4, &nodes, |p, q, moment| match (p, q) {
+            (3, 0) => self.moment_xxx += moment,
+            (2, 1) => self.moment_xxy += moment,
+            (1, 2) => self.moment_xyy += moment,
+            (0, 3) => self.moment_yyy += moment,
+            (4, 0) => self.moment_xxxx += moment,
+            (3, 1) => self.moment_xxxy += moment,
+            (2, 2) => self.moment_xxyy += moment,
+            (1, 3) => self.moment_xyyy += moment,
+            (0, 4) => self.moment_yyyy += moment,
+            _ => unreachable!(),
+        });
+    }
+}
+
+impl GreenStatistics {
+    /// Compute exact statistics for a circle
+    pub fn from_circle(circle: &Circle) -> Self {
+        GreenStatistics::from_ellipse(&Ellipse::from(*circle))
+    }
+
+    /// Compute exact statistics for an ellipse
+    ///
+    /// The ellipse is traced counter-clockwise, so its area is positive.
+    pub fn from_ellipse(ellipse: &Ellipse) -> Self {
+        let (radii, x_rotation) = ellipse.radii_and_rotation();
+        let mut statistics = GreenStatistics::default();
+        statistics.add_arc(&Arc::new(ellipse.center(), radii, 0.0, TAU, x_rotation));
+        statistics
+    }
+
+    /// Add the contribution of a line, quadratic or cubic Bézier segment
+    ///
+    /// The segment is integrated exactly. Together with [GreenStatistics::add_arc]
+    /// and [GreenStatistics::add_svg_arc], this allows paths mixing arcs and other
+    /// segments, such as SVG paths, to be integrated segment by segment.
+    pub fn add_segment(&mut self, seg: PathSeg) {
+        match seg {
+            PathSeg::Line(l) => self.handle_line(coords(l.p0), coords(l.p1)),
+            PathSeg::Quad(q) => self.handle_quad(coords(q.p0), coords(q.p1), coords(q.p2)),
+            PathSeg::Cubic(c) => {
+                self.handle_cubic(coords(c.p0), coords(c.p1), coords(c.p2), coords(c.p3))
+            }
+        }
+    }
+
+    /// Add the contribution of an elliptical arc segment
+    ///
+    /// The arc is integrated exactly, rather than being approximated by cubic
+    /// Béziers as [Arc::append_iter] does. Like any other segment, its moments
+    /// only balance once its contour is closed, so the rest of the contour must
+    /// be added too unless the arc is a complete ellipse.
+    pub fn add_arc(&mut self, arc: &Arc) {
+        self.handle_arc(arc);
+    }
+
+    /// Add the contribution of an SVG arc command (`A`)
+    ///
+    /// As with [GreenStatistics::add_arc], the arc is integrated exactly. An arc
+    /// which SVG treats as a straight line (e.g. one with a zero radius) is
+    /// integrated as a line.
+    pub fn add_svg_arc(&mut self, arc: &SvgArc) {
+        match Arc::from_svg_arc(arc) {
+            Some(arc) => self.handle_arc(&arc),
+            None => self.handle_line(coords(arc.from), coords(arc.to)),
+        }
+    }
+
+    /// Add the contribution of a conic (rational quadratic Bézier) segment
+    ///
+    /// A conic with a weight of one is an ordinary quadratic, and one with a
+    /// weight of zero is a straight line between its end points; both are
+    /// integrated exactly. Otherwise the moments are integrated numerically,
+    /// subdividing the segment until the estimated error in each is below
+    /// `accuracy` relative to its size: moments of order `n` are held to
+    /// `accuracy * r^(n + 2)`, where `r` is the largest absolute coordinate of the
+    /// control points.
+    ///
+    /// The weight must not be negative, as a conic with a weight of minus one or
+    /// less passes through infinity. A negative weight makes every moment NaN, so
+    /// that [CurveStatistics::check] reports [StatisticsError::NonFinite].
+    pub fn add_conic(&mut self, conic: &ConicBez, accuracy: f64) {
+        if conic.weight == 1.0 {
+            self.handle_quad(coords(conic.p0), coords(conic.p1), coords(conic.p2));
+            return;
+        }
+        if conic.weight == 0.0 {
+            self.handle_line(coords(conic.p0), coords(conic.p2));
+            return;
+        }
+        if conic.weight < 0.0 || conic.weight.is_nan() {
+            *self += GreenStatistics::from_table(&[[f64::NAN; MAX_ORDER + 1]; MAX_ORDER + 1]);
+            return;
+        }
+        let scale = [conic.p0, conic.p1, conic.p2]
+            .iter()
+            .fold(0.0_f64, |scale, p| scale.max(p.x.abs()).max(p.y.abs()));
+        let table = integrate_conic(conic, 0.0..1.0, accuracy, scale, 0);
+        *self += GreenStatistics::from_table(&table);
+    }
+
+    /// Accumulate all moments of an elliptical arc
+    ///
+    /// Along the arc, each integrand `-x^p y^(q+1) / (q+1) dx/dθ` is a trigonometric
+    /// polynomial in θ of degree at most `p + q + 2`. Sampling it at enough equally
+    /// spaced angles recovers its Fourier coefficients exactly, and those can be
+    /// integrated over the arc's sweep in closed form.
+    fn handle_arc(&mut self, arc: &Arc) {
+        let (sin_rot, cos_rot) = arc.x_rotation.sin_cos();
+        let rotate =
+            |u: f64, v: f64| Vec2::new(u * cos_rot - v * sin_rot, u * sin_rot + v * cos_rot);
+        let points: Vec<(Point, Vec2)> = (0..ARC_SAMPLES)
+            .map(|k| {
+                let (sin, cos) = (TAU * k as f64 / ARC_SAMPLES as f64).sin_cos();
+                (
+                    arc.center + rotate(arc.radii.x * cos, arc.radii.y * sin),
+                    rotate(-arc.radii.x * sin, arc.radii.y * cos),
+                )
+            })
+            .collect();
+        let mut samples = [[[0.0; ARC_SAMPLES]; MAX_ORDER + 1]; MAX_ORDER + 1];
+        for (p_order, row) in samples.iter_mut().enumerate() {
+            for (q_order, values) in row.iter_mut().take(MAX_ORDER + 1 - p_order).enumerate() {
+                for (value, (p, dp)) in values.iter_mut().zip(&points) {
+                    *value = -p.x.powi(p_order as i32) * p.y.powi(q_order as i32 + 1)
+                        / (q_order + 1) as f64
+                        * dp.x;
+                }
+            }
+        }
+        let start = arc.start_angle;
+        let end = arc.start_angle + arc.sweep_angle;
+        let mut table = [[0.0; MAX_ORDER + 1]; MAX_ORDER + 1];
+        for i in 0..=MAX_ORDER {
+            for j in 0..=(MAX_ORDER - i) {
+                table[i][j] = integrate_trigonometric(&samples[i][j], start, end);
+            }
+        }
+        *self += GreenStatistics::from_table(&table);
+    }
+}
+
+/// Moments are additive over disjoint regions, so the statistics of separate
+/// contours or components can be summed to give the statistics of the whole.
+impl<S: Scalar> Add for GenericGreenStatistics<S> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        self.zip_with(&rhs, |a, b| a + b)
+    }
+}
+
+impl<S: Scalar> AddAssign for GenericGreenStatistics<S> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+/// Subtracting statistics removes a region, e.g. a contour deleted in an editor.
+impl<S: Scalar> Sub for GenericGreenStatistics<S> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self.zip_with(&rhs, |a, b| a - b)
+    }
+}
+
+impl<S: Scalar> SubAssign for GenericGreenStatistics<S> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+/// Negating statistics is equivalent to reversing the direction of the path.
+impl<S: Scalar> Neg for GenericGreenStatistics<S> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::default() - self
+    }
+}
+
+impl<S: Scalar> Sum for GenericGreenStatistics<S> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+impl<'a, S: Scalar> Sum<&'a GenericGreenStatistics<S>> for GenericGreenStatistics<S> {
+    fn sum<I: Iterator<Item = &'a GenericGreenStatistics<S>>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+/// The highest degree of trigonometric polynomial integrated along an arc
+const ARC_DEGREE: usize = MAX_ORDER + 2;
+/// The number of samples needed to recover a trigonometric polynomial of [ARC_DEGREE]
+const ARC_SAMPLES: usize = 2 * ARC_DEGREE + 1;
+
+/// Integrate a trigonometric polynomial, given by samples at equally spaced
+/// angles around the circle, from `start` to `end`
+fn integrate_trigonometric(samples: &[f64; ARC_SAMPLES], start: f64, end: f64) -> f64 {
+    let n = ARC_SAMPLES as f64;
+    let mut integral = samples.iter().sum::<f64>() / n * (end - start);
+    for harmonic in 1..=ARC_DEGREE {
+        let (mut a, mut b) = (0.0, 0.0);
+        for (k, sample) in samples.iter().enumerate() {
+            let (sin, cos) = (TAU * (harmonic * k) as f64 / n).sin_cos();
+            a += 2.0 / n * sample * cos;
+            b += 2.0 / n * sample * sin;
+        }
+        let h = harmonic as f64;
+        integral += a * ((h * end).sin() - (h * start).sin()) / h
+            - b * ((h * end).cos() - (h * start).cos()) / h;
+    }
+    integral
+}
+
+/// The deepest that a conic is subdivided when integrating along it
+const MAX_CONIC_DEPTH: usize = 12;
+
+/// Gauss-Legendre estimate of the moments of a conic over a parameter range
+fn conic_gauss_legendre(conic: &ConicBez, range: &std::ops::Range<f64>) -> MomentTable {
+    let half = (range.end - range.start) / 2.0;
+    let mid = (range.end + range.start) / 2.0;
+    let mut table = [[0.0; MAX_ORDER + 1]; MAX_ORDER + 1];
+    for &(wi, xi) in GAUSS_LEGENDRE_COEFFS_16 {
+        let t = mid + half * xi;
+        let p = conic.eval(t);
+        let dx = conic.deriv(t).x * wi * half;
+        for (i, row) in table.iter_mut().enumerate() {
+            for (j, moment) in row.iter_mut().take(MAX_ORDER + 1 - i).enumerate() {
+                *moment -= p.x.powi(i as i32) * p.y.powi(j as i32 + 1) / (j + 1) as f64 * dx;
+            }
+        }
+    }
+    table
+}
+
+/// Integrate the moments of a conic, subdividing until the estimates converge
+fn integrate_conic(
+    conic: &ConicBez,
+    range: std::ops::Range<f64>,
+    accuracy: f64,
+    scale: f64,
+    depth: usize,
+) -> MomentTable {
+    let mid = (range.start + range.end) / 2.0;
+    let whole = conic_gauss_legendre(conic, &range);
+    let (left, right) = (
+        conic_gauss_legendre(conic, &(range.start..mid)),
+        conic_gauss_legendre(conic, &(mid..range.end)),
+    );
+    let mut halves = [[0.0; MAX_ORDER + 1]; MAX_ORDER + 1];
+    let mut converged = true;
+    for i in 0..=MAX_ORDER {
+        for j in 0..=(MAX_ORDER - i) {
+            halves[i][j] = left[i][j] + right[i][j];
+            // The moment of order i + j has units of length^(i + j + 2)
+            let tolerance = accuracy * scale.powi((i + j + 2) as i32);
+            converged &= (halves[i][j] - whole[i][j]).abs() <= tolerance;
+        }
+    }
+    if converged || depth >= MAX_CONIC_DEPTH {
+        return halves;
+    }
+    let left = integrate_conic(conic, range.start..mid, accuracy / 2.0, scale, depth + 1);
+    let right = integrate_conic(conic, mid..range.end, accuracy / 2.0, scale, depth + 1);
+    let mut table = [[0.0; MAX_ORDER + 1]; MAX_ORDER + 1];
+    for i in 0..=MAX_ORDER {
+        for j in 0..=(MAX_ORDER - i) {
+            table[i][j] = left[i][j] + right[i][j];
+        }
+    }
+    table
+}
+
+/// A table of zero moments
+fn zero_table<S: Scalar>() -> MomentTable<S> {
+    [[S::default(); MAX_ORDER + 1]; MAX_ORDER + 1]
+}
+
+/// The coordinates of a point in the scalar type
+fn coords<S: Scalar>(p: Point) -> (S, S) {
+    (S::from_f64(p.x), S::from_f64(p.y))
+}
+
+/// Multiply a polynomial in x and y by the linear form `cx x + cy y + c0`
+///
+/// Terms beyond [MAX_ORDER] are dropped.
+fn mul_linear<S: Scalar>(poly: &MomentTable<S>, cx: S, cy: S, c0: S) -> MomentTable<S> {
+    let mut out = zero_table();
+    for i in 0..=MAX_ORDER {
+        for j in 0..=(MAX_ORDER - i) {
+            let coeff = poly[i][j];
+            out[i][j] += coeff * c0;
+            if i + j < MAX_ORDER {
+                out[i + 1][j] += coeff * cx;
+                out[i][j + 1] += coeff * cy;
+            }
+        }
+    }
+    out
+}
+
+/// Walks the elements of a path, accumulating statistics
+pub(crate) struct Accumulator<S = f64> {
+    moments: GenericGreenStatistics<S>,
+    /// The moments before the current contour started, to discard it if needed
+    snapshot: GenericGreenStatistics<S>,
+    start_pt: Point,
+    start_index: usize,
+    cur: Point,
+    /// Whether the current contour has been closed, so that drawing continues
+    /// with a new contour from its start point
+    closed: bool,
+    policy: ClosePolicy,
+}
+
+impl<S: Scalar> Accumulator<S> {
+    pub(crate) fn new(policy: ClosePolicy) -> Self {
+        Accumulator {
+            moments: GenericGreenStatistics::default(),
+            snapshot: GenericGreenStatistics::default(),
+            start_pt: Point::ZERO,
+            start_index: 0,
+            cur: Point::ZERO,
+            closed: false,
+            policy,
+        }
+    }
+
+    /// Add the path element at `index` in the path
+    pub(crate) fn element(&mut self, index: usize, el: PathEl) -> Result<(), StatisticsError> {
+        match el {
+            PathEl::MoveTo(p) => {
+                self.end_contour()?;
+                self.snapshot = self.moments;
+                self.start_pt = p;
+                self.start_index = index;
+                self.cur = p;
+                self.closed = false;
+            }
+            PathEl::LineTo(p) => {
+                self.begin_drawing(index);
+                self.moments.handle_line(coords(self.cur), coords(p));
+                self.cur = p;
+            }
+            PathEl::QuadTo(p0, p1) => {
+                self.begin_drawing(index);
+                self.moments
+                    .handle_quad(coords(self.cur), coords(p0), coords(p1));
+                self.cur = p1;
+            }
+            PathEl::CurveTo(p1, p2, p3) => {
+                self.begin_drawing(index);
+                self.moments
+                    .handle_cubic(coords(self.cur), coords(p1), coords(p2), coords(p3));
+                self.cur = p3;
+            }
+            PathEl::ClosePath => {
+                if self.cur != self.start_pt {
+                    self.moments
+                        .handle_line(coords(self.cur), coords(self.start_pt));
+                    self.cur = self.start_pt;
+                }
+                self.closed = true;
+            }
+        }
+        Ok(())
+    }
+
+    /// Start a new contour at `index` if drawing continues after a
+    /// [PathEl::ClosePath], as [split_contours] does
+    fn begin_drawing(&mut self, index: usize) {
+        if self.closed {
+            self.snapshot = self.moments;
+            self.start_index = index;
+            self.closed = false;
+        }
+    }
+
+    /// Apply the close policy to the current contour
+    fn end_contour(&mut self) -> Result<(), StatisticsError> {
+        if self.cur == self.start_pt {
+            return Ok(());
+        }
+        match self.policy {
+            ClosePolicy::Implicit => self
+                .moments
+                .handle_line(coords(self.cur), coords(self.start_pt)),
+            ClosePolicy::IgnoreOpen => self.moments = self.snapshot,
+            ClosePolicy::Error => {
+                return Err(StatisticsError::OpenContour {
+                    start_index: self.start_index,
+                })
+            }
+        }
+        self.cur = self.start_pt;
+        Ok(())
+    }
+
+    pub(crate) fn finish(mut self) -> Result<GenericGreenStatistics<S>, StatisticsError> {
+        self.end_contour()?;
+        Ok(self.moments)
+    }
+}
+
+impl Accumulator {
+    /// Add a conic segment from the current point, at `index` in the path
+    pub(crate) fn conic(&mut self, index: usize, p1: Point, p2: Point, weight: f64, accuracy: f64) {
+        self.begin_drawing(index);
+        self.moments
+            .add_conic(&ConicBez::new(self.cur, p1, p2, weight), accuracy);
+        self.cur = p2;
     }
 }
 
@@ -526,35 +1169,73 @@ where
     &'a T: IntoIterator<Item = PathEl>,
 {
     fn green_statistics(&'a self) -> GreenStatistics {
-        let mut moments = GreenStatistics::default();
-        let mut start_pt: Point = Point::ZERO;
-        let mut cur: Point = Point::ZERO;
-        for el in self {
-            match el {
-                PathEl::MoveTo(p) => {
-                    start_pt = p;
-                    cur = p;
-                }
-                PathEl::LineTo(p) => {
-                    moments.handle_line(cur, p);
-                    cur = p;
-                }
-                PathEl::QuadTo(p0, p1) => {
-                    moments.handle_quad(cur, p0, p1);
-                    cur = p1;
-                }
-                PathEl::CurveTo(p1, p2, p3) => {
-                    moments.handle_cubic(cur, p1, p2, p3);
-                    cur = p3;
-                }
-                PathEl::ClosePath => {
-                    if cur != start_pt {
-                        moments.handle_line(cur, start_pt);
-                        cur = start_pt;
-                    }
-                }
-            }
+        match self.green_statistics_with_policy(ClosePolicy::Implicit) {
+            Ok(moments) => moments,
+            Err(_) => unreachable!("implicitly closing contours cannot fail"),
+        }
+    }
+
+    fn green_statistics_with_policy(
+        &'a self,
+        policy: ClosePolicy,
+    ) -> Result<GreenStatistics, StatisticsError> {
+        let mut accumulator = Accumulator::new(policy);
+        for (index, el) in self.into_iter().enumerate() {
+            accumulator.element(index, el)?;
         }
-        moments
+        accumulator.finish()
+    }
+
+    fn green_statistics_filled(&'a self, fill_rule: FillRule) -> GreenStatistics {
+        filled_statistics(self, fill_rule)
+    }
+
+    fn green_statistics_normalized(&'a self) -> GreenStatistics {
+        let contours = split_contours(self);
+        let holes = find_holes(&contours);
+        contours
+            .iter()
+            .zip(holes)
+            .map(|(contour, is_hole)| {
+                let statistics = contour.path.green_statistics();
+                // Outer contours should have a positive area, holes a negative one
+                if (statistics.area < 0.0) != is_hole {
+                    -statistics
+                } else {
+                    statistics
+                }
+            })
+            .sum()
+    }
+
+    fn contour_directions(&'a self) -> Vec<ContourDirection> {
+        let contours = split_contours(self);
+        let holes = find_holes(&contours);
+        contours
+            .iter()
+            .zip(holes)
+            .map(|(contour, is_hole)| ContourDirection {
+                start_index: contour.start_index,
+                orientation: Orientation::from_area(contour.path.green_statistics().area),
+                is_hole,
+            })
+            .collect()
+    }
+
+    fn green_statistics_gradients(&'a self) -> StatisticsGradients {
+        statistics_gradients(self)
+    }
+
+    fn green_statistics_by_contour(&'a self) -> ContourBreakdown<GreenStatistics> {
+        let contours: Vec<_> = split_contours(self)
+            .into_iter()
+            .map(|contour| ContourStatistics {
+                start_index: contour.start_index,
+                closed: contour.closed,
+                statistics: contour.path.green_statistics(),
+            })
+            .collect();
+        let total = contours.iter().map(|c| c.statistics).sum();
+        ContourBreakdown { contours, total }
     }
 }
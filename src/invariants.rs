@@ -0,0 +1,73 @@
+use kurbo::{Affine, Vec2};
+
+use crate::{CurveStatistics, GreenStatistics};
+
+impl GreenStatistics {
+    /// The moments about the center of mass
+    ///
+    /// In the result, `moment_x` and `moment_y` are zero (up to rounding), and the
+    /// remaining moments are the central moments μ_pq of the shape.
+    pub fn central_moments(&self) -> GreenStatistics {
+        self.transform(Affine::translate(-self.center_of_mass().to_vec2()))
+    }
+
+    /// Find the normalized central moment η_pq
+    ///
+    /// This is the central moment μ_pq divided by μ₀₀^(1 + (p + q) / 2), which makes
+    /// it invariant under translation and uniform scaling. The division uses the
+    /// signed area for its sign and the absolute area for its magnitude, so the
+    /// result does not depend on the direction of the path.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p + q` is more than four, the highest order tracked.
+    pub fn normalized_central_moment(&self, p: usize, q: usize) -> f64 {
+        assert!(p + q <= 4, "moments of order {} are not tracked", p + q);
+        let central = self.central_moments().moment_table();
+        let area = central[0][0];
+        central[p][q] / area / area.abs().powf((p + q) as f64 / 2.0)
+    }
+
+    /// Find the seven Hu moment invariants
+    ///
+    /// These combine the normalized central moments of orders two and three into
+    /// quantities which are also invariant under rotation, so they describe the
+    /// shape independently of its position, size and orientation. The first six
+    /// are also unchanged by reflection, while the seventh changes sign.
+    pub fn hu_invariants(&self) -> [f64; 7] {
+        let eta = |p, q| self.normalized_central_moment(p, q);
+        let (n20, n11, n02) = (eta(2, 0), eta(1, 1), eta(0, 2));
+        let (n30, n21, n12, n03) = (eta(3, 0), eta(2, 1), eta(1, 2), eta(0, 3));
+        let (a, b) = (n30 + n12, n21 + n03);
+        let (c, d) = (n30 - 3.0 * n12, 3.0 * n21 - n03);
+        [
+            n20 + n02,
+            (n20 - n02).powi(2) + 4.0 * n11.powi(2),
+            c.powi(2) + d.powi(2),
+            a.powi(2) + b.powi(2),
+            c * a * (a.powi(2) - 3.0 * b.powi(2)) + d * b * (3.0 * a.powi(2) - b.powi(2)),
+            (n20 - n02) * (a.powi(2) - b.powi(2)) + 4.0 * n11 * a * b,
+            d * a * (a.powi(2) - 3.0 * b.powi(2)) - c * b * (3.0 * a.powi(2) - b.powi(2)),
+        ]
+    }
+
+    /// Find the variance of the path relative to its area
+    ///
+    /// This is [CurveStatistics::variance] divided by the absolute area, i.e. the
+    /// normalized central moments η₂₀ and η₀₂, so it is unchanged when the path is
+    /// scaled uniformly.
+    pub fn scale_invariant_variance(&self) -> Vec2 {
+        Vec2::new(
+            self.normalized_central_moment(2, 0).abs(),
+            self.normalized_central_moment(0, 2).abs(),
+        )
+    }
+
+    /// Find the covariance of the path relative to its area
+    ///
+    /// This is [CurveStatistics::covariance] divided by the absolute area, i.e. the
+    /// normalized central moment η₁₁.
+    pub fn scale_invariant_covariance(&self) -> f64 {
+        self.normalized_central_moment(1, 1)
+    }
+}
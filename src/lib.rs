@@ -1,10 +1,15 @@
 //! A library for computing statistics on paths
 //!
-//! This library provides methods for computing statistics on paths, such as the area, center of mass, variance, covariance, correlation, and slant.
+//! This library provides methods for computing statistics on paths, such as the area, center of mass, variance, covariance, correlation, slant, skewness and kurtosis.
 //!
 //! It implements two mechanisms for computing statistics, one based on Green's theorem, and
 //! the other using the control only. The library is a straight port of the Python library
-//! `fontTools.pens.statisticsPen`.
+//! `fontTools.pens.statisticsPen`. A third mechanism flattens the path to a polygon first,
+//! and is useful as a reference implementation.
+//!
+//! Both the Green's theorem and control point statistics can be accumulated in any
+//! [Scalar] type, via [GenericGreenStatistics] and [GenericControlStatistics]; the usual
+//! [GreenStatistics] and [ControlStatistics] accumulate in `f64`.
 //!
 //! While it is expected to be used on [kurbo::BezPath] objects, it can be used on any object that
 //! can iterate over [kurbo::PathEl] objects.
@@ -28,24 +33,184 @@
 //! assert_relative_eq!(stats.correlation(), 0.006042487913362581, epsilon = f64::EPSILON);
 //! assert_relative_eq!(stats.slant(), 0.0035283020889418774, epsilon = f64::EPSILON);
 //! ```
-pub use control::ControlStatistics;
-pub use green::GreenStatistics;
+pub use axes::PrincipalAxes;
+pub use boundary::BoundaryStatistics;
+pub use conic::{ConicBez, ConicPathEl};
+pub use contour::{
+    ClosePolicy, ContourBreakdown, ContourDirection, ContourStatistics, Orientation,
+};
+pub use control::{ControlStatistics, GenericControlStatistics};
+pub use distance::{shape_distance, DistanceComponent, DistanceOptions, ShapeDistance};
+pub use error::StatisticsError;
+pub use features::{
+    FeatureNormalization, FeatureVector, FEATURE_COUNT, FEATURE_NAMES, FEATURE_VERSION,
+};
+pub use fill::FillRule;
+pub use gradient::{PointGradient, StatisticsGradients};
+pub use green::{GenericGreenStatistics, GreenStatistics};
 use kurbo::{Point, Vec2};
+pub use moments::Moments;
+pub use options::{
+    compute_statistics, MethodStatistics, OrientationHandling, Statistics, StatisticsMethod,
+    StatisticsOptions,
+};
+pub use orthogonal::{LegendreMoments, ZernikeMoment, ZernikeMoments, MAX_ORTHOGONAL_ORDER};
+pub use robust::RobustStatistics;
+pub use scalar::Scalar;
+pub use weighted::{ControlAccumulator, ControlWeighting, WeightedControlStatistics};
+mod axes;
+mod boundary;
+mod conic;
+mod contour;
 mod control;
+mod distance;
+mod error;
+mod features;
+mod fill;
+mod flatten;
+mod gradient;
 mod green;
+mod invariants;
+mod moments;
+mod options;
+mod orthogonal;
+mod poly;
+mod robust;
+mod scalar;
+mod weighted;
 
 /// Compute statistics on a path using the Green's theorem method
 pub trait ComputeGreenStatistics<'a> {
     /// Compute statistics for the curve using the Green's theorem method
+    ///
+    /// Open contours are closed implicitly; see [ClosePolicy::Implicit].
     fn green_statistics(&'a self) -> GreenStatistics;
+    /// Compute statistics for the curve, treating open contours according to a [ClosePolicy]
+    fn green_statistics_with_policy(
+        &'a self,
+        policy: ClosePolicy,
+    ) -> Result<GreenStatistics, StatisticsError>;
+    /// Compute statistics for the region actually filled by the curve under a fill rule
+    ///
+    /// Unlike [ComputeGreenStatistics::green_statistics], which integrates the signed
+    /// winding number, overlapping contours are only counted once and self-intersecting
+    /// contours do not cancel out, so the statistics describe the visible ink. The area
+    /// of the result is always positive.
+    fn green_statistics_filled(&'a self, fill_rule: FillRule) -> GreenStatistics;
+    /// Compute statistics for the curve independently of the direction of its contours
+    ///
+    /// Each contour's contribution is oriented so that outer contours have a positive
+    /// area and holes a negative one, whichever way they are drawn. Outlines drawn
+    /// clockwise (as in TrueType) and counter-clockwise (as in CFF) therefore give
+    /// identical results, with a positive total area.
+    fn green_statistics_normalized(&'a self) -> GreenStatistics;
+    /// Report the direction of each contour of the curve, and whether it is a hole
+    fn contour_directions(&'a self) -> Vec<ContourDirection>;
+    /// Compute statistics for each contour of the curve using the Green's theorem method
+    fn green_statistics_by_contour(&'a self) -> ContourBreakdown<GreenStatistics>;
+    /// Compute statistics for the curve along with their gradients with respect to
+    /// each of its points
+    ///
+    /// This is useful for adjusting an outline to reach target statistics. Open
+    /// contours are closed implicitly.
+    fn green_statistics_gradients(&'a self) -> StatisticsGradients;
 }
 
 /// Compute statistics on a path using the control polygon method
 pub trait ComputeControlStatistics<'a> {
     /// Compute statistics for the curve using the control polygon method
     fn control_statistics(&'a self) -> ControlStatistics;
+    /// Compute statistics for each contour of the curve using the control polygon method
+    fn control_statistics_by_contour(&'a self) -> ContourBreakdown<ControlStatistics>;
+    /// Compute the area moments of the curve's control polygons
+    ///
+    /// See [GenericControlStatistics::polygon_statistics].
+    fn control_polygon_statistics(&'a self) -> GreenStatistics;
+    /// Compute statistics for the control points of the curve, weighting each point
+    ///
+    /// The points are streamed through a [ControlAccumulator] rather than stored.
+    fn weighted_control_statistics(
+        &'a self,
+        weighting: ControlWeighting,
+    ) -> WeightedControlStatistics;
+}
+
+/// Compute statistics on a path which may contain conic segments
+pub trait ComputeConicStatistics<'a> {
+    /// Compute statistics for the curve using the Green's theorem method
+    ///
+    /// Conic segments are integrated as described in [GreenStatistics::add_conic],
+    /// to within `accuracy`; all other segments are integrated exactly. Open
+    /// contours are closed implicitly.
+    fn conic_statistics(&'a self, accuracy: f64) -> GreenStatistics;
+}
+
+/// Compute area moments of a path up to any order
+pub trait ComputeMoments<'a> {
+    /// Compute the area moments of the curve up to `max_order`
+    ///
+    /// Every segment is integrated exactly. Open contours are closed implicitly.
+    fn moments(&'a self, max_order: usize) -> Moments;
+}
+
+/// Compute orthogonal moment descriptors of a path
+pub trait ComputeOrthogonalMoments<'a> {
+    /// Compute the Legendre moments of the curve up to order `max_order`, after
+    /// normalizing it to its bounding box
+    ///
+    /// Fails with [StatisticsError::DegenerateArea] if the curve encloses no area,
+    /// as then its bounding box cannot be normalized, and with
+    /// [StatisticsError::UnsupportedOrder] if `max_order` is more than
+    /// [MAX_ORTHOGONAL_ORDER].
+    fn legendre_moments(&'a self, max_order: usize) -> Result<LegendreMoments, StatisticsError>;
+    /// Compute the Zernike moments of the curve up to radial order `max_order`,
+    /// after normalizing it to a disk about its center of mass
+    ///
+    /// The magnitudes of the moments do not change when the curve is rotated.
+    /// Fails with [StatisticsError::DegenerateArea] if the curve encloses no area,
+    /// as then it has no center of mass, and with
+    /// [StatisticsError::UnsupportedOrder] if `max_order` is more than
+    /// [MAX_ORTHOGONAL_ORDER].
+    fn zernike_moments(&'a self, max_order: usize) -> Result<ZernikeMoments, StatisticsError>;
+}
+
+/// Compute statistics on a path by flattening it to a polygon
+pub trait ComputeFlattenedStatistics<'a> {
+    /// Compute statistics for the curve by flattening it and integrating the polygon exactly
+    ///
+    /// Each segment is approximated by lines to within `tolerance` using [kurbo::flatten],
+    /// so the result converges on [ComputeGreenStatistics::green_statistics] as the
+    /// tolerance shrinks. This makes it a useful check on the closed-form results, and
+    /// it works for any source of path elements.
+    fn flattened_statistics(&'a self, tolerance: f64) -> GreenStatistics;
 }
 
+/// Compute statistics on a path without losing precision far from the origin
+pub trait ComputeRobustStatistics<'a> {
+    /// Compute statistics for the curve using the Green's theorem method, robustly
+    ///
+    /// The moments of high order grow rapidly with distance from the origin, so an
+    /// outline placed far from it (in a large font, or in a layout) leaves few
+    /// significant digits for the variance and higher statistics. Here each contour
+    /// is integrated relative to its start point using compensated summation, the
+    /// contours are combined using the parallel axis theorem, and the result is
+    /// held about the center of mass. Open contours are closed implicitly.
+    fn robust_statistics(&'a self) -> RobustStatistics;
+}
+
+/// Compute statistics of the outline of a path, weighted by arc length
+pub trait ComputeBoundaryStatistics<'a> {
+    /// Compute statistics for the outline of the curve
+    ///
+    /// Curved segments are integrated numerically; `accuracy` bounds the error in
+    /// the arc length of each segment, as for [kurbo::ParamCurveArclen::arclen].
+    fn boundary_statistics(&'a self, accuracy: f64) -> BoundaryStatistics;
+}
+
+/// The magnitude at or below which [CurveStatistics::correlation] and
+/// [CurveStatistics::slant] are reported as zero
+pub const DEFAULT_SNAP_THRESHOLD: f64 = 0.001;
+
 /// Statistics for a curve returned by either of the two methods
 pub trait CurveStatistics {
     /// Calculate the signed area of a path
@@ -56,6 +221,21 @@ pub trait CurveStatistics {
     fn variance(&self) -> Vec2;
     /// Find the covariance of the path
     fn covariance(&self) -> f64;
+    /// Find the skewness of the path along each axis
+    ///
+    /// A positive value in `y` means the tail of the distribution points upwards,
+    /// i.e. the shape is bottom-heavy. The default implementation, for statistics
+    /// which do not track third-order moments, returns NaN.
+    fn skewness(&self) -> Vec2 {
+        Vec2::new(f64::NAN, f64::NAN)
+    }
+    /// Find the excess kurtosis of the path along each axis
+    ///
+    /// The default implementation, for statistics which do not track fourth-order
+    /// moments, returns NaN.
+    fn kurtosis(&self) -> Vec2 {
+        Vec2::new(f64::NAN, f64::NAN)
+    }
 
     /// Find the standard deviation of the path
     fn stddev(&self) -> Vec2 {
@@ -68,9 +248,15 @@ pub trait CurveStatistics {
     /// Uses the Pearson product-moment correlation coefficient
     /// from <https://en.wikipedia.org/wiki/Pearson_product-moment_correlation_coefficient>
     fn correlation(&self) -> f64 {
+        self.correlation_with_threshold(DEFAULT_SNAP_THRESHOLD)
+    }
+
+    /// Find the correlation of the path, reporting zero if its magnitude is no
+    /// larger than `threshold`
+    fn correlation_with_threshold(&self, threshold: f64) -> f64 {
         let stddev = self.stddev();
         let correlation = (self.covariance() / (stddev.x * stddev.y)).clamp(-1.0, 1.0);
-        if correlation.abs() > 0.001 {
+        if correlation.abs() > threshold {
             correlation
         } else {
             0.0
@@ -79,13 +265,103 @@ pub trait CurveStatistics {
 
     /// Find the slant of the path
     fn slant(&self) -> f64 {
+        self.slant_with_threshold(DEFAULT_SNAP_THRESHOLD)
+    }
+
+    /// Find the slant of the path, reporting zero if its magnitude is no larger
+    /// than `threshold`
+    fn slant_with_threshold(&self, threshold: f64) -> f64 {
         let slant = self.covariance() / self.variance().y;
-        if slant.abs() > 0.001 {
+        if slant.abs() > threshold {
             slant
         } else {
             0.0
         }
     }
+
+    /// Find the principal axes of the path
+    ///
+    /// These are the eigenvalues and eigenvectors of the covariance matrix, which
+    /// give the true orientation of the shape; unlike [CurveStatistics::slant], they
+    /// do not depend on treating one axis as the independent variable.
+    fn principal_axes(&self) -> PrincipalAxes {
+        PrincipalAxes::new(self.center_of_mass(), self.variance(), self.covariance())
+    }
+
+    /// Check that the statistics are well defined
+    ///
+    /// By default this requires a finite, non-zero area. The `try_*` methods call
+    /// this before computing anything.
+    fn check(&self) -> Result<(), StatisticsError> {
+        let area = self.area();
+        if !area.is_finite() {
+            Err(StatisticsError::NonFinite)
+        } else if area == 0.0 {
+            Err(StatisticsError::DegenerateArea)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Find the center of mass of the path, failing if it is not well defined
+    fn try_center_of_mass(&self) -> Result<Point, StatisticsError> {
+        self.check()?;
+        finite(self.center_of_mass(), Point::is_finite)
+    }
+
+    /// Find the variance of the path, failing if it is not well defined
+    fn try_variance(&self) -> Result<Vec2, StatisticsError> {
+        self.check()?;
+        finite(self.variance(), Vec2::is_finite)
+    }
+
+    /// Find the covariance of the path, failing if it is not well defined
+    fn try_covariance(&self) -> Result<f64, StatisticsError> {
+        self.check()?;
+        finite(self.covariance(), f64::is_finite)
+    }
+
+    /// Find the correlation of the path, failing if either variance is zero
+    fn try_correlation(&self) -> Result<f64, StatisticsError> {
+        nonzero_variance(self.try_variance()?, true, true)?;
+        finite(self.correlation(), f64::is_finite)
+    }
+
+    /// Find the slant of the path, failing if the variance in `y` is zero
+    fn try_slant(&self) -> Result<f64, StatisticsError> {
+        nonzero_variance(self.try_variance()?, false, true)?;
+        finite(self.slant(), f64::is_finite)
+    }
+
+    /// Find the skewness of the path, failing if either variance is zero
+    fn try_skewness(&self) -> Result<Vec2, StatisticsError> {
+        nonzero_variance(self.try_variance()?, true, true)?;
+        finite(self.skewness(), Vec2::is_finite)
+    }
+
+    /// Find the excess kurtosis of the path, failing if either variance is zero
+    fn try_kurtosis(&self) -> Result<Vec2, StatisticsError> {
+        nonzero_variance(self.try_variance()?, true, true)?;
+        finite(self.kurtosis(), Vec2::is_finite)
+    }
+}
+
+/// Pass on a value if it is finite
+fn finite<T: Copy>(value: T, is_finite: impl Fn(T) -> bool) -> Result<T, StatisticsError> {
+    if is_finite(value) {
+        Ok(value)
+    } else {
+        Err(StatisticsError::NonFinite)
+    }
+}
+
+/// Check that the variance along the given axes is not zero
+fn nonzero_variance(variance: Vec2, x: bool, y: bool) -> Result<(), StatisticsError> {
+    if (x && variance.x == 0.0) || (y && variance.y == 0.0) {
+        Err(StatisticsError::DegenerateVariance)
+    } else {
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -169,6 +445,1249 @@ mod tests {
         assert_relative_eq!(stats.area(), b.area(), epsilon = f64::EPSILON);
     }
 
+    #[test]
+    fn test_green_higher_order() {
+        /* A right triangle, whose marginals are triangular distributions */
+        let b = BezPath::from_svg("M0 0L1 0L0 1Z").expect("Failed to parse path");
+        let stats = b.green_statistics();
+        assert_relative_eq!(stats.moment_xxx, 1.0 / 20.0, epsilon = 1e-12);
+        assert_relative_eq!(stats.moment_xxy, 1.0 / 60.0, epsilon = 1e-12);
+        assert_relative_eq!(stats.moment_xxyy, 1.0 / 180.0, epsilon = 1e-12);
+        let skew = 2.0 * 2.0_f64.sqrt() / 5.0;
+        assert_relative_eq!(stats.skewness().x, skew, epsilon = 1e-12);
+        assert_relative_eq!(stats.skewness().y, skew, epsilon = 1e-12);
+        assert_relative_eq!(stats.kurtosis().x, -0.6, epsilon = 1e-12);
+        assert_relative_eq!(stats.kurtosis().y, -0.6, epsilon = 1e-12);
+
+        /* The same curve as a quadratic and as its degree-elevated cubic */
+        let quad = BezPath::from_svg("M0 0Q50 200 100 0Z").expect("Failed to parse path");
+        let cubic = BezPath::from_svg("M0 0C33.333333333333336 133.33333333333334 66.66666666666667 133.33333333333334 100 0Z")
+            .expect("Failed to parse path");
+        let quad = quad.green_statistics();
+        let cubic = cubic.green_statistics();
+        assert_relative_eq!(quad.moment_yyyy, cubic.moment_yyyy, max_relative = 1e-12);
+        assert_relative_eq!(quad.moment_xxxy, cubic.moment_xxxy, max_relative = 1e-12);
+        assert!(quad.skewness().y > 0.0);
+    }
+
+    #[test]
+    fn test_green_higher_order_segments() {
+        /* Exact moments of the region under a parabola, drawn clockwise */
+        let exact = [
+            -4000000000.0 / 3.0,
+            -16000000000.0 / 21.0,
+            -16000000000.0 / 21.0,
+            -64000000000.0 / 63.0,
+            -2000000000000.0 / 21.0,
+            -1000000000000.0 / 21.0,
+            -8000000000000.0 / 189.0,
+            -3200000000000.0 / 63.0,
+            -51200000000000.0 / 693.0,
+        ];
+        let quad = BezPath::from_svg("M0 0Q50 200 100 0Z").expect("Failed to parse path");
+        let cubic = BezPath::from_svg("M0 0C33.333333333333336 133.33333333333334 66.66666666666667 133.33333333333334 100 0Z")
+            .expect("Failed to parse path");
+        for stats in [quad.green_statistics(), cubic.green_statistics()] {
+            let moments = [
+                stats.moment_xxx,
+                stats.moment_xxy,
+                stats.moment_xyy,
+                stats.moment_yyy,
+                stats.moment_xxxx,
+                stats.moment_xxxy,
+                stats.moment_xxyy,
+                stats.moment_xyyy,
+                stats.moment_yyyy,
+            ];
+            for (moment, exact) in moments.iter().zip(exact) {
+                assert_relative_eq!(*moment, exact, max_relative = 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_principal_axes() {
+        /* A 200x20 rectangle rotated by 30 degrees about the origin */
+        let rect = kurbo::Rect::new(-100.0, -10.0, 100.0, 10.0).to_path(0.1);
+        let angle = 30.0_f64.to_radians();
+        let b = kurbo::Affine::rotate(angle) * rect;
+        let axes = b.green_statistics().principal_axes();
+        assert_relative_eq!(axes.angle, angle, epsilon = 1e-12);
+        assert_relative_eq!(axes.eigenvalues.0, 200.0 * 200.0 / 12.0, epsilon = 1e-9);
+        assert_relative_eq!(axes.eigenvalues.1, 20.0 * 20.0 / 12.0, epsilon = 1e-9);
+        assert_relative_eq!(axes.eigenvectors.0.x, angle.cos(), epsilon = 1e-12);
+        assert_relative_eq!(axes.eigenvectors.1.x, -angle.sin(), epsilon = 1e-12);
+        assert_relative_eq!(axes.eccentricity, 0.99_f64.sqrt(), epsilon = 1e-12);
+        let (radii, rotation) = axes.ellipse.radii_and_rotation();
+        assert_relative_eq!(radii.x, 200.0 / 3.0_f64.sqrt(), epsilon = 1e-9);
+        assert_relative_eq!(radii.y, 20.0 / 3.0_f64.sqrt(), epsilon = 1e-9);
+        assert_relative_eq!(rotation, angle, epsilon = 1e-12);
+        assert_relative_eq!(axes.ellipse.center().x, 0.0, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_green_transform() {
+        let b = BezPath::from_svg("M173 575Q173 541 171.5 511.5Q170 482 168 465H173Q196 499 236.0 522.0Q276 545 339 545Q439 545 499.5 475.5Q560 406 560 268Q560 130 499.0 60.0Q438 -10 339 -10Q276 -10 236.0 13.0Q196 36 173 68H166L148 0H85V760H173ZM324 472Q239 472 206.0 423.0Q173 374 173 271V267Q173 168 205.5 115.5Q238 63 326 63Q398 63 433.5 116.0Q469 169 469 269Q469 472 324 472Z").expect("Failed to parse path");
+        for affine in [
+            kurbo::Affine::translate((120.0, -40.0)),
+            kurbo::Affine::skew(0.2, 0.0) * kurbo::Affine::scale_non_uniform(1.5, 0.75),
+            kurbo::Affine::rotate(0.3).then_translate((10.0, 20.0).into()),
+            kurbo::Affine::FLIP_X,
+        ] {
+            let expected = (affine * b.clone()).green_statistics();
+            let found = b.green_statistics().transform(affine);
+            assert_relative_eq!(found.area(), expected.area(), max_relative = 1e-12);
+            assert_relative_eq!(found.moment_x, expected.moment_x, max_relative = 1e-12);
+            assert_relative_eq!(found.moment_xy, expected.moment_xy, max_relative = 1e-12);
+            assert_relative_eq!(found.moment_yy, expected.moment_yy, max_relative = 1e-12);
+            assert_relative_eq!(
+                found.moment_xxyy,
+                expected.moment_xxyy,
+                max_relative = 1e-10
+            );
+            assert_relative_eq!(found.slant(), expected.slant(), epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_green_arithmetic() {
+        let outer = BezPath::from_svg("M173 575Q173 541 171.5 511.5Q170 482 168 465H173Q196 499 236.0 522.0Q276 545 339 545Q439 545 499.5 475.5Q560 406 560 268Q560 130 499.0 60.0Q438 -10 339 -10Q276 -10 236.0 13.0Q196 36 173 68H166L148 0H85V760H173Z").expect("Failed to parse path");
+        let inner = BezPath::from_svg("M324 472Q239 472 206.0 423.0Q173 374 173 271V267Q173 168 205.5 115.5Q238 63 326 63Q398 63 433.5 116.0Q469 169 469 269Q469 472 324 472Z").expect("Failed to parse path");
+        let mut whole = outer.clone();
+        whole.extend(inner.iter());
+        let whole = whole.green_statistics();
+        let parts = [outer.green_statistics(), inner.green_statistics()];
+
+        let sum: GreenStatistics = parts.iter().sum();
+        assert_relative_eq!(sum.area(), whole.area(), max_relative = 1e-12);
+        assert_relative_eq!(sum.moment_xy, whole.moment_xy, max_relative = 1e-12);
+        assert_relative_eq!(sum.moment_yyyy, whole.moment_yyyy, max_relative = 1e-12);
+
+        let mut removed = whole;
+        removed -= parts[1];
+        assert_relative_eq!(removed.moment_x, parts[0].moment_x, max_relative = 1e-12);
+        assert_relative_eq!((-removed).area(), -parts[0].area(), max_relative = 1e-12);
+
+        let raw = GreenStatistics::from_moments(
+            whole.area(),
+            whole.moment_x,
+            whole.moment_y,
+            whole.moment_xx,
+            whole.moment_xy,
+            whole.moment_yy,
+        );
+        assert_relative_eq!(raw.slant(), whole.slant(), epsilon = f64::EPSILON);
+    }
+
+    #[test]
+    fn test_by_contour() {
+        /* Noto Sans Regular 'b', two paths, with a hole in the middle */
+        let b = BezPath::from_svg("M173 575Q173 541 171.5 511.5Q170 482 168 465H173Q196 499 236.0 522.0Q276 545 339 545Q439 545 499.5 475.5Q560 406 560 268Q560 130 499.0 60.0Q438 -10 339 -10Q276 -10 236.0 13.0Q196 36 173 68H166L148 0H85V760H173ZM324 472Q239 472 206.0 423.0Q173 374 173 271V267Q173 168 205.5 115.5Q238 63 326 63Q398 63 433.5 116.0Q469 169 469 269Q469 472 324 472Z").expect("Failed to parse path");
+        let breakdown = b.green_statistics_by_contour();
+        assert_eq!(breakdown.contours.len(), 2);
+        assert_eq!(breakdown.contours[0].start_index, 0);
+        assert_eq!(breakdown.contours[1].start_index, 18);
+        assert!(breakdown.contours.iter().all(|c| c.closed));
+        assert_eq!(breakdown.contours[0].orientation(), Orientation::Clockwise);
+        assert_eq!(
+            breakdown.contours[1].orientation(),
+            Orientation::CounterClockwise
+        );
+        let whole = b.green_statistics();
+        assert_relative_eq!(breakdown.total.area(), whole.area(), max_relative = 1e-12);
+        assert_relative_eq!(
+            breakdown.total.moment_yy,
+            whole.moment_yy,
+            max_relative = 1e-12
+        );
+
+        let open =
+            BezPath::from_svg("M0 0L10 0L10 10M20 20L30 20L30 30Z").expect("Failed to parse path");
+        let breakdown = open.control_statistics_by_contour();
+        assert_eq!(breakdown.contours.len(), 2);
+        assert_eq!(breakdown.contours[1].start_index, 3);
+        assert!(!breakdown.contours[0].closed);
+        assert!(breakdown.contours[1].closed);
+        approx_eq_point(
+            breakdown.contours[1].statistics.center_of_mass(),
+            80.0 / 3.0,
+            70.0 / 3.0,
+        );
+
+        /* Closing a contour twice does not start another */
+        let mut twice = BezPath::from_svg("M0 0L10 0L10 10Z").expect("Failed to parse path");
+        twice.close_path();
+        assert_eq!(twice.green_statistics_by_contour().contours.len(), 1);
+        assert_eq!(twice.contour_directions().len(), 1);
+    }
+
+    #[test]
+    fn test_close_policy() {
+        let closed =
+            BezPath::from_svg("M0 0L10 0L10 10ZM20 20L30 20L30 30Z").expect("Failed to parse path");
+        let open =
+            BezPath::from_svg("M0 0L10 0L10 10M20 20L30 20L30 30").expect("Failed to parse path");
+        let expected = closed.green_statistics();
+        let found = open.green_statistics();
+        assert_relative_eq!(found.area(), expected.area(), epsilon = f64::EPSILON);
+        approx_eq_point(
+            found.center_of_mass(),
+            expected.center_of_mass().x,
+            expected.center_of_mass().y,
+        );
+
+        let half_open =
+            BezPath::from_svg("M0 0L10 0L10 10M20 20L30 20L30 30Z").expect("Failed to parse path");
+        let ignored = half_open
+            .green_statistics_with_policy(ClosePolicy::IgnoreOpen)
+            .expect("Ignoring open contours cannot fail");
+        assert_relative_eq!(ignored.center_of_mass().x, 80.0 / 3.0, epsilon = 1e-12);
+        assert_relative_eq!(ignored.center_of_mass().y, 70.0 / 3.0, epsilon = 1e-12);
+        assert_eq!(
+            half_open
+                .green_statistics_with_policy(ClosePolicy::Error)
+                .unwrap_err(),
+            StatisticsError::OpenContour { start_index: 0 }
+        );
+        assert!(closed
+            .green_statistics_with_policy(ClosePolicy::Error)
+            .is_ok());
+
+        /* Drawing on after a ClosePath starts a new contour */
+        let continued =
+            BezPath::from_svg("M0 0L10 0L10 10ZL-10 0L-10 -10").expect("Failed to parse path");
+        let ignored = continued
+            .green_statistics_with_policy(ClosePolicy::IgnoreOpen)
+            .expect("Ignoring open contours cannot fail");
+        assert_relative_eq!(ignored.area(), 50.0);
+        assert_eq!(
+            continued
+                .green_statistics_with_policy(ClosePolicy::Error)
+                .unwrap_err(),
+            StatisticsError::OpenContour { start_index: 4 }
+        );
+    }
+
+    #[test]
+    fn test_fill_rule() {
+        let squares =
+            BezPath::from_svg("M0 0H10V10H0ZM5 5H15V15H5Z").expect("Failed to parse path");
+        assert_relative_eq!(squares.green_statistics().area(), 200.0);
+        let nonzero = squares.green_statistics_filled(FillRule::NonZero);
+        assert_relative_eq!(nonzero.area(), 175.0, epsilon = 1e-9);
+        approx_eq_point(nonzero.center_of_mass(), 7.5, 7.5);
+        let evenodd = squares.green_statistics_filled(FillRule::EvenOdd);
+        assert_relative_eq!(evenodd.area(), 150.0, epsilon = 1e-9);
+
+        let bowtie = BezPath::from_svg("M0 0L10 10L10 0L0 10Z").expect("Failed to parse path");
+        assert_relative_eq!(bowtie.green_statistics().area(), 0.0);
+        let filled = bowtie.green_statistics_filled(FillRule::NonZero);
+        assert_relative_eq!(filled.area(), 50.0, epsilon = 1e-9);
+        assert_relative_eq!(filled.center_of_mass().x, 5.0, epsilon = 1e-9);
+
+        let mut circles = kurbo::Circle::new((0.0, 0.0), 10.0).to_path(1e-6);
+        circles.extend(kurbo::Circle::new((10.0, 0.0), 10.0).to_path(1e-6));
+        let lens = 200.0 * 0.5_f64.acos() - 5.0 * 300.0_f64.sqrt();
+        let filled = circles.green_statistics_filled(FillRule::NonZero);
+        assert_relative_eq!(
+            filled.area(),
+            200.0 * std::f64::consts::PI - lens,
+            max_relative = 1e-5
+        );
+        assert_relative_eq!(filled.center_of_mass().x, 5.0, epsilon = 1e-6);
+        assert_relative_eq!(filled.center_of_mass().y, 0.0, epsilon = 1e-6);
+
+        /* Circles which touch at a point, wherever they are */
+        for offset in [0.0, 1000.0] {
+            let mut tangent = kurbo::Circle::new((offset, offset), 10.0).to_path(1e-6);
+            tangent.extend(kurbo::Circle::new((offset + 20.0, offset), 10.0).to_path(1e-6));
+            let filled = tangent.green_statistics_filled(FillRule::NonZero);
+            assert_relative_eq!(
+                filled.area(),
+                tangent.green_statistics().area(),
+                max_relative = 1e-9
+            );
+        }
+
+        /* A cubic with a loop: the loop and the lobe below it wind in opposite directions */
+        let looped =
+            BezPath::from_svg("M0 0C150 100 -50 100 100 0Z").expect("Failed to parse path");
+        let filled = looped.green_statistics_filled(FillRule::NonZero);
+        let reversed = looped
+            .reverse_subpaths()
+            .green_statistics_filled(FillRule::NonZero);
+        assert_relative_eq!(looped.green_statistics().area(), -1500.0, epsilon = 1e-9);
+        assert_relative_eq!(filled.area(), 2341.6975764367, max_relative = 1e-9);
+        assert_relative_eq!(filled.area(), reversed.area(), max_relative = 1e-9);
+    }
+
+    #[test]
+    fn test_fill_rule_overlaps() {
+        /* Contours sharing part of an edge, as in overlapping stems and bars */
+        let cases = [
+            ("M0 1H10V11H0Z M0 1H20V6H0Z", 150.0),
+            ("M0 1H10V11H0Z M2 1H8V21H2Z", 160.0),
+            ("M0 1H10V11H0Z M10 1H20V11H10Z", 200.0),
+            ("M3 1H13V11H3Z M3 1H13V11H3Z", 100.0),
+        ];
+        for (svg, area) in cases {
+            let path = BezPath::from_svg(svg).expect("Failed to parse path");
+            let filled = path.green_statistics_filled(FillRule::NonZero);
+            assert_relative_eq!(filled.area(), area, epsilon = 1e-9);
+        }
+        let duplicate =
+            BezPath::from_svg("M3 1H13V11H3Z M3 1H13V11H3Z").expect("Failed to parse path");
+        let filled = duplicate.green_statistics_filled(FillRule::NonZero);
+        approx_eq_point(filled.center_of_mass(), 8.0, 6.0);
+        let filled = duplicate.green_statistics_filled(FillRule::EvenOdd);
+        assert_relative_eq!(filled.area(), 0.0, epsilon = 1e-9);
+
+        /* A duplicated curved contour */
+        let mut circles = kurbo::Circle::new((5.0, 5.0), 10.0).to_path(1e-6);
+        circles.extend(kurbo::Circle::new((5.0, 5.0), 10.0).to_path(1e-6));
+        let filled = circles.green_statistics_filled(FillRule::NonZero);
+        let single = kurbo::Circle::new((5.0, 5.0), 10.0)
+            .to_path(1e-6)
+            .green_statistics();
+        assert_relative_eq!(filled.area(), single.area(), max_relative = 1e-9);
+        assert_relative_eq!(filled.center_of_mass().x, 5.0, epsilon = 1e-9);
+        assert_relative_eq!(filled.center_of_mass().y, 5.0, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_normalized_orientation() {
+        /* Noto Sans Regular 'b', drawn with TrueType (clockwise) outer contours */
+        let b = BezPath::from_svg("M173 575Q173 541 171.5 511.5Q170 482 168 465H173Q196 499 236.0 522.0Q276 545 339 545Q439 545 499.5 475.5Q560 406 560 268Q560 130 499.0 60.0Q438 -10 339 -10Q276 -10 236.0 13.0Q196 36 173 68H166L148 0H85V760H173ZM324 472Q239 472 206.0 423.0Q173 374 173 271V267Q173 168 205.5 115.5Q238 63 326 63Q398 63 433.5 116.0Q469 169 469 269Q469 472 324 472Z").expect("Failed to parse path");
+        let directions = b.contour_directions();
+        assert_eq!(directions[0].orientation, Orientation::Clockwise);
+        assert!(!directions[0].is_hole);
+        assert_eq!(directions[1].orientation, Orientation::CounterClockwise);
+        assert!(directions[1].is_hole);
+
+        let truetype = b.green_statistics_normalized();
+        let cff = b.reverse_subpaths().green_statistics_normalized();
+        assert_relative_eq!(truetype.area(), -b.green_statistics().area());
+        assert_relative_eq!(truetype.area(), cff.area(), max_relative = 1e-12);
+        assert_relative_eq!(truetype.moment_xy, cff.moment_xy, max_relative = 1e-12);
+        assert_relative_eq!(truetype.slant(), cff.slant(), epsilon = 1e-12);
+
+        /* A hole drawn in the same direction as its outer contour */
+        let same =
+            BezPath::from_svg("M0 0H30V30H0ZM10 10H20V20H10Z").expect("Failed to parse path");
+        assert_relative_eq!(same.green_statistics().area(), 1000.0);
+        assert_relative_eq!(same.green_statistics_normalized().area(), 800.0);
+
+        /* Overlapping outer contours, one starting inside the other */
+        let overlapping =
+            BezPath::from_svg("M0 0H10V100H0Z M5 50H60V60H5Z").expect("Failed to parse path");
+        assert_relative_eq!(overlapping.green_statistics().area(), 1550.0);
+        let directions = overlapping.contour_directions();
+        assert!(!directions[0].is_hole);
+        assert!(!directions[1].is_hole);
+        assert_relative_eq!(overlapping.green_statistics_normalized().area(), 1550.0);
+        let options = StatisticsOptions {
+            orientation: OrientationHandling::Normalized,
+            ..Default::default()
+        };
+        let statistics = compute_statistics(&overlapping.reverse_subpaths(), &options).unwrap();
+        assert_relative_eq!(statistics.area(), 1550.0);
+
+        /* Two overlapping glyphs: each counter is a hole, even inside the other outer */
+        let mut pair = b.clone();
+        pair.extend(kurbo::Affine::translate((37.0, 23.0)) * &b);
+        let directions = pair.contour_directions();
+        let holes: Vec<bool> = directions.iter().map(|d| d.is_hole).collect();
+        assert_eq!(holes, [false, true, false, true]);
+        assert_relative_eq!(
+            pair.green_statistics_normalized().area(),
+            -pair.green_statistics().area(),
+            max_relative = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_boundary() {
+        let square = BezPath::from_svg("M0 0H30V30H0Z").expect("Failed to parse path");
+        let stats = square.boundary_statistics(1e-9);
+        assert_relative_eq!(stats.length, 120.0);
+        assert_relative_eq!(stats.area(), square.area());
+        approx_eq_point(stats.center_of_mass(), 15.0, 15.0);
+        assert_relative_eq!(stats.variance().x, 900.0 / 6.0, epsilon = 1e-9);
+        assert_relative_eq!(stats.covariance(), 0.0, epsilon = 1e-9);
+
+        let circle = kurbo::Circle::new((200.0, 300.0), 100.0).to_path(1e-9);
+        let stats = circle.boundary_statistics(1e-9);
+        assert_relative_eq!(
+            stats.length,
+            200.0 * std::f64::consts::PI,
+            max_relative = 1e-9
+        );
+        assert_relative_eq!(stats.center_of_mass().x, 200.0, max_relative = 1e-9);
+        assert_relative_eq!(stats.center_of_mass().y, 300.0, max_relative = 1e-9);
+        assert_relative_eq!(stats.variance().x, 5000.0, max_relative = 1e-8);
+        assert_relative_eq!(stats.variance().y, 5000.0, max_relative = 1e-8);
+        /* The arcsine distribution has excess kurtosis -3/2 */
+        assert_relative_eq!(stats.kurtosis().x, -1.5, epsilon = 1e-6);
+
+        /* Open paths have a boundary even though they have no area */
+        let stroke = BezPath::from_svg("M0 0Q50 100 100 0").expect("Failed to parse path");
+        let stats = stroke.boundary_statistics(1e-9);
+        assert_relative_eq!(
+            stats.length,
+            kurbo::ParamCurveArclen::arclen(
+                &kurbo::QuadBez::new((0.0, 0.0), (50.0, 100.0), (100.0, 0.0)),
+                1e-12
+            ),
+            max_relative = 1e-9
+        );
+        assert_relative_eq!(stats.center_of_mass().x, 50.0, max_relative = 1e-9);
+    }
+
+    #[test]
+    fn test_flattened() {
+        /* Noto Sans Regular 'c', i.e. a single quad path */
+        let b = BezPath::from_svg("M300 -10Q229 -10 173.5 19.0Q118 48 86.5 109.0Q55 170 55 265Q55 364 88.0 426.0Q121 488 177.5 517.0Q234 546 306 546Q347 546 385.0 537.5Q423 529 447 517L420 444Q396 453 364.0 461.0Q332 469 304 469Q146 469 146 266Q146 169 184.5 117.5Q223 66 299 66Q343 66 376.5 75.0Q410 84 438 97V19Q411 5 378.5 -2.5Q346 -10 300 -10Z").expect("Failed to parse path");
+        let exact = b.green_statistics();
+        let coarse = b.flattened_statistics(1.0);
+        let fine = b.flattened_statistics(1e-4);
+        assert!(
+            (fine.area() - exact.area()).abs() < (coarse.area() - exact.area()).abs(),
+            "flattening should converge as the tolerance shrinks"
+        );
+        assert_relative_eq!(fine.area(), exact.area(), max_relative = 1e-6);
+        assert_relative_eq!(
+            fine.center_of_mass().x,
+            exact.center_of_mass().x,
+            max_relative = 1e-6
+        );
+        assert_relative_eq!(fine.variance().y, exact.variance().y, max_relative = 1e-6);
+        assert_relative_eq!(fine.skewness().y, exact.skewness().y, max_relative = 1e-4);
+
+        /* Polygons are integrated exactly */
+        let slash = BezPath::from_svg("M362 714 96 0H10L276 714Z").expect("Failed to parse path");
+        assert_relative_eq!(
+            slash.flattened_statistics(1.0).moment_xy,
+            slash.green_statistics().moment_xy
+        );
+    }
+
+    #[test]
+    fn test_arcs() {
+        use std::f64::consts::PI;
+        let circle = kurbo::Circle::new((200.0, 300.0), 100.0);
+        let exact = GreenStatistics::from_circle(&circle);
+        assert_relative_eq!(exact.area(), PI * 10000.0, max_relative = 1e-14);
+        assert_relative_eq!(exact.center_of_mass().x, 200.0, max_relative = 1e-14);
+        assert_relative_eq!(exact.center_of_mass().y, 300.0, max_relative = 1e-14);
+        assert_relative_eq!(exact.variance().x, 2500.0, max_relative = 1e-12);
+        assert_relative_eq!(exact.covariance(), 0.0, epsilon = 1e-9);
+        assert_relative_eq!(exact.kurtosis().x, -1.0, epsilon = 1e-9);
+        /* The cubic approximation is close, but not exact */
+        let approximate = circle.to_path(0.1).green_statistics();
+        assert_relative_eq!(approximate.area(), exact.area(), max_relative = 1e-3);
+
+        let ellipse = kurbo::Ellipse::new((10.0, 20.0), (30.0, 12.0), 0.4);
+        let axes = GreenStatistics::from_ellipse(&ellipse).principal_axes();
+        assert_relative_eq!(axes.ellipse.radii().x, 30.0, max_relative = 1e-12);
+        assert_relative_eq!(axes.ellipse.radii().y, 12.0, max_relative = 1e-12);
+        assert_relative_eq!(axes.angle, 0.4, max_relative = 1e-12);
+
+        /* A half disc from an SVG arc, closed along the diameter */
+        let mut half = GreenStatistics::default();
+        half.add_svg_arc(&kurbo::SvgArc {
+            from: (10.0, 0.0).into(),
+            to: (-10.0, 0.0).into(),
+            radii: (10.0, 10.0).into(),
+            x_rotation: 0.0,
+            large_arc: false,
+            sweep: true,
+        });
+        half.add_segment(kurbo::PathSeg::Line(kurbo::Line::new(
+            (-10.0, 0.0),
+            (10.0, 0.0),
+        )));
+        assert_relative_eq!(half.area(), 50.0 * PI, max_relative = 1e-12);
+        assert_relative_eq!(
+            half.center_of_mass().y,
+            40.0 / (3.0 * PI),
+            max_relative = 1e-12
+        );
+
+        /* SVG treats an arc with a zero radius as a straight line */
+        let mut line = GreenStatistics::default();
+        line.add_svg_arc(&kurbo::SvgArc {
+            from: (-10.0, 0.0).into(),
+            to: (10.0, 5.0).into(),
+            radii: (0.0, 0.0).into(),
+            x_rotation: 0.0,
+            large_arc: false,
+            sweep: true,
+        });
+        let mut expected = GreenStatistics::default();
+        expected.add_segment(kurbo::PathSeg::Line(kurbo::Line::new(
+            (-10.0, 0.0),
+            (10.0, 5.0),
+        )));
+        assert_eq!(line.moment_xxyy, expected.moment_xxyy);
+    }
+
+    #[test]
+    fn test_conics() {
+        use std::f64::consts::PI;
+        /* A quarter disc, with the arc as a conic of weight cos(45°) */
+        let quarter = vec![
+            ConicPathEl::Path(kurbo::PathEl::MoveTo((0.0, 0.0).into())),
+            ConicPathEl::Path(kurbo::PathEl::LineTo((100.0, 0.0).into())),
+            ConicPathEl::ConicTo((100.0, 100.0).into(), (0.0, 100.0).into(), 0.5_f64.sqrt()),
+            ConicPathEl::Path(kurbo::PathEl::ClosePath),
+        ];
+        let stats = quarter.conic_statistics(1e-9);
+        assert_relative_eq!(stats.area(), 2500.0 * PI, max_relative = 1e-12);
+        assert_relative_eq!(
+            stats.center_of_mass().x,
+            400.0 / (3.0 * PI),
+            max_relative = 1e-12
+        );
+        let disc = GreenStatistics::from_circle(&kurbo::Circle::new((0.0, 0.0), 100.0));
+        assert_relative_eq!(
+            stats.moment_xxyy * 4.0,
+            disc.moment_xxyy,
+            max_relative = 1e-9
+        );
+
+        /* The accuracy is relative, so large conics converge as quickly */
+        let large = vec![
+            ConicPathEl::Path(kurbo::PathEl::MoveTo((0.0, 0.0).into())),
+            ConicPathEl::Path(kurbo::PathEl::LineTo((10000.0, 0.0).into())),
+            ConicPathEl::ConicTo(
+                (10000.0, 10000.0).into(),
+                (0.0, 10000.0).into(),
+                0.5_f64.sqrt(),
+            ),
+            ConicPathEl::Path(kurbo::PathEl::ClosePath),
+        ];
+        assert_relative_eq!(
+            large.conic_statistics(1e-9).area(),
+            2.5e7 * PI,
+            max_relative = 1e-12
+        );
+
+        /* A negative weight passes through infinity */
+        let negative = vec![
+            ConicPathEl::Path(kurbo::PathEl::MoveTo((0.0, 0.0).into())),
+            ConicPathEl::ConicTo((100.0, 100.0).into(), (0.0, 100.0).into(), -1.0),
+            ConicPathEl::Path(kurbo::PathEl::ClosePath),
+        ];
+        assert_eq!(
+            negative.conic_statistics(1e-9).check(),
+            Err(StatisticsError::NonFinite)
+        );
+
+        /* A conic with a weight of one is an ordinary quadratic */
+        let quad = BezPath::from_svg("M0 0Q50 200 100 0Z").expect("Failed to parse path");
+        let conic: Vec<ConicPathEl> = vec![
+            kurbo::PathEl::MoveTo((0.0, 0.0).into()).into(),
+            ConicPathEl::ConicTo((50.0, 200.0).into(), (100.0, 0.0).into(), 1.0),
+            kurbo::PathEl::ClosePath.into(),
+        ];
+        assert_relative_eq!(
+            conic.conic_statistics(1e-9).moment_yyyy,
+            quad.green_statistics().moment_yyyy
+        );
+    }
+
+    #[test]
+    fn test_generic_scalar() {
+        let b = BezPath::from_svg("M362 714 96 0H10L276 714Z").expect("Failed to parse path");
+        let stats = b.green_statistics();
+        /* Accumulating in f64 is the default */
+        let generic = GenericGreenStatistics::<f64>::from_path(&b);
+        assert_eq!(generic.moment_yyyy, stats.moment_yyyy);
+        /* Accumulating in f32 loses precision, but not the shape */
+        let single = GenericGreenStatistics::<f32>::from_path(&b);
+        assert_relative_eq!(single.area(), stats.area(), max_relative = 1e-6);
+        assert_relative_eq!(single.center_of_mass().x, 186.0, max_relative = 1e-5);
+        assert_relative_eq!(single.center_of_mass().y, 357.0, max_relative = 1e-5);
+        assert_relative_eq!(single.covariance(), 15827.0, max_relative = 1e-3);
+        let control = GenericControlStatistics::<f32>::from_path(&b);
+        let expected = b.control_statistics().center_of_mass();
+        assert_relative_eq!(control.center_of_mass().x, expected.x, max_relative = 1e-6);
+        assert_relative_eq!(control.center_of_mass().y, expected.y, max_relative = 1e-6);
+    }
+
+    #[test]
+    fn test_robust() {
+        /* A two-contour 'o' far from the origin */
+        let mut b = BezPath::from_svg("M0 0H300V400H0ZM100 100V300H200V100Z")
+            .expect("Failed to parse path");
+        let near = b.green_statistics();
+        b.apply_affine(kurbo::Affine::translate((3.0e6, -7.0e6)));
+        let plain = b.green_statistics();
+        let robust = b.robust_statistics();
+        assert_relative_eq!(robust.area(), near.area(), max_relative = 1e-12);
+        assert_relative_eq!(
+            robust.center_of_mass().x,
+            near.center_of_mass().x + 3.0e6,
+            max_relative = 1e-15
+        );
+        assert_relative_eq!(robust.variance().x, near.variance().x, max_relative = 1e-9);
+        assert_relative_eq!(robust.variance().y, near.variance().y, max_relative = 1e-9);
+        assert_relative_eq!(robust.kurtosis().y, near.kurtosis().y, max_relative = 1e-9);
+        /* The naive computation has lost the variance altogether */
+        assert!((plain.variance().y - near.variance().y).abs() > near.variance().y * 1e-3);
+        /* Even the first moments are more accurate */
+        assert_eq!(robust.global().moment_x, 100000.0 * (3.0e6 + 150.0));
+    }
+
+    #[test]
+    fn test_fallible() {
+        let empty = BezPath::new();
+        assert_eq!(
+            empty.green_statistics().try_center_of_mass(),
+            Err(StatisticsError::DegenerateArea)
+        );
+        assert_eq!(
+            empty.control_statistics().try_variance(),
+            Err(StatisticsError::EmptyInput)
+        );
+        assert_eq!(
+            empty.boundary_statistics(1e-9).try_center_of_mass(),
+            Err(StatisticsError::DegenerateLength)
+        );
+
+        /* A single point has a center but no spread */
+        let point = ControlStatistics::new(vec![Point::new(1.0, 2.0)]);
+        assert_eq!(point.covariance(), 0.0);
+        assert_eq!(point.try_center_of_mass(), Ok(Point::new(1.0, 2.0)));
+        assert_eq!(point.try_covariance(), Ok(0.0));
+        assert_eq!(
+            point.try_correlation(),
+            Err(StatisticsError::DegenerateVariance)
+        );
+
+        /* A flat shape has no vertical variance, so no slant */
+        let flat = BezPath::from_svg("M0 0L10 0L20 0Z").expect("Failed to parse path");
+        assert_eq!(
+            flat.control_statistics().try_slant(),
+            Err(StatisticsError::DegenerateVariance)
+        );
+
+        let nan = BezPath::from_vec(vec![
+            kurbo::PathEl::MoveTo(Point::new(f64::NAN, 0.0)),
+            kurbo::PathEl::LineTo(Point::new(1.0, 1.0)),
+            kurbo::PathEl::LineTo(Point::new(1.0, 0.0)),
+        ]);
+        assert_eq!(
+            nan.green_statistics().try_variance(),
+            Err(StatisticsError::NonFinite)
+        );
+        assert_eq!(
+            nan.control_statistics().try_covariance(),
+            Err(StatisticsError::NonFinite)
+        );
+
+        let b = BezPath::from_svg("M362 714 96 0H10L276 714Z").expect("Failed to parse path");
+        let stats = b.green_statistics();
+        assert_eq!(stats.try_slant(), Ok(stats.slant()));
+        assert_eq!(
+            StatisticsError::DegenerateArea.to_string(),
+            "the path encloses no area"
+        );
+
+        /* Statistics which only track moments up to order two */
+        struct SecondOrder;
+        impl CurveStatistics for SecondOrder {
+            fn area(&self) -> f64 {
+                1.0
+            }
+            fn center_of_mass(&self) -> Point {
+                Point::ZERO
+            }
+            fn variance(&self) -> Vec2 {
+                Vec2::new(1.0, 1.0)
+            }
+            fn covariance(&self) -> f64 {
+                0.0
+            }
+        }
+        assert!(SecondOrder.skewness().x.is_nan());
+        assert_eq!(SecondOrder.try_kurtosis(), Err(StatisticsError::NonFinite));
+    }
+
+    #[test]
+    fn test_control_contours() {
+        /* An 'o': the shoelace area is taken per contour */
+        let b = BezPath::from_svg("M0 0H300V400H0ZM100 100V300H200V100Z")
+            .expect("Failed to parse path");
+        let stats = b.control_statistics();
+        assert_eq!(stats.area(), 100000.0);
+        assert_eq!(stats.area(), b.green_statistics().area());
+        let contours: Vec<_> = stats.contours().collect();
+        assert_eq!(contours.len(), 2);
+        assert_eq!(contours[0].len(), 4);
+        assert_eq!(contours[1][0], (100.0, 100.0));
+        assert_eq!(stats.points().len(), 8);
+        /* Drawing after a close starts a new contour */
+        let b = BezPath::from_svg("M0 0H10V10ZL-10 10H-10Z").expect("Failed to parse path");
+        assert_eq!(b.control_statistics().contours().count(), 2);
+        /* ...which continues from the start point of the contour just closed */
+        let b = BezPath::from_vec(vec![
+            kurbo::PathEl::MoveTo(Point::new(0.0, 0.0)),
+            kurbo::PathEl::LineTo(Point::new(10.0, 0.0)),
+            kurbo::PathEl::LineTo(Point::new(10.0, 10.0)),
+            kurbo::PathEl::ClosePath,
+            kurbo::PathEl::LineTo(Point::new(-10.0, 0.0)),
+            kurbo::PathEl::LineTo(Point::new(-10.0, -10.0)),
+            kurbo::PathEl::ClosePath,
+        ]);
+        let stats = b.control_statistics();
+        assert_eq!(stats.contours().nth(1).unwrap()[0], (0.0, 0.0));
+        assert_eq!(stats.area(), 100.0);
+        assert_eq!(stats.area(), b.green_statistics().area());
+        assert_eq!(b.control_polygon_statistics().area(), 100.0);
+        let streamed = b.weighted_control_statistics(ControlWeighting::Uniform);
+        assert_eq!(streamed.area(), 100.0);
+        assert_eq!(streamed.center_of_mass(), stats.center_of_mass());
+    }
+
+    #[test]
+    fn test_weighted_control() {
+        /* Uniform weighting matches the stored points */
+        let b = BezPath::from_svg("M300 -10Q229 -10 173.5 19.0Q118 48 86.5 109.0Q55 170 55 265Q55 364 88.0 426.0Q121 488 177.5 517.0Q234 546 306 546Q347 546 385.0 537.5Q423 529 447 517L420 444Q396 453 364.0 461.0Q332 469 304 469Q146 469 146 266Q146 169 184.5 117.5Q223 66 299 66Q343 66 376.5 75.0Q410 84 438 97V19Q411 5 378.5 -2.5Q346 -10 300 -10ZM0 0H300V400H0Z").expect("Failed to parse path");
+        let stored = b.control_statistics();
+        let streamed = b.weighted_control_statistics(ControlWeighting::Uniform);
+        assert_eq!(streamed.area(), stored.area());
+        assert_eq!(streamed.center_of_mass(), stored.center_of_mass());
+        assert_eq!(streamed.variance(), stored.variance());
+        assert_eq!(streamed.covariance(), stored.covariance());
+        assert_relative_eq!(
+            streamed.skewness().y,
+            stored.skewness().y,
+            max_relative = 1e-9
+        );
+        assert_relative_eq!(
+            streamed.kurtosis().x,
+            stored.kurtosis().x,
+            max_relative = 1e-9
+        );
+
+        /* Off-curve points can be left out, or given less weight */
+        let quad = BezPath::from_svg("M0 0Q50 100 100 0Z").expect("Failed to parse path");
+        let on_curve = quad.weighted_control_statistics(ControlWeighting::OnCurve);
+        assert_eq!(on_curve.weight, 2.0);
+        assert_eq!(on_curve.center_of_mass(), Point::new(50.0, 0.0));
+        let light = quad.weighted_control_statistics(ControlWeighting::OffCurve(0.5));
+        assert_eq!(light.center_of_mass(), Point::new(50.0, 20.0));
+
+        /* Weighting by edge length finds the middle of the rectangle, despite the
+        extra point along its bottom edge */
+        let rect = BezPath::from_svg("M0 0L10 0L20 0L20 10L0 10Z").expect("Failed to parse path");
+        assert_eq!(
+            rect.control_statistics().center_of_mass(),
+            Point::new(10.0, 4.0)
+        );
+        let by_length = rect.weighted_control_statistics(ControlWeighting::EdgeLength);
+        assert_eq!(by_length.weight, 60.0);
+        assert_eq!(by_length.center_of_mass(), Point::new(10.0, 5.0));
+
+        /* The accumulator can be fed element by element */
+        let mut accumulator = ControlAccumulator::new(ControlWeighting::Uniform);
+        for el in rect.elements() {
+            accumulator.add(*el);
+        }
+        assert_eq!(accumulator.finish().area(), 200.0);
+    }
+
+    #[test]
+    fn test_control_polygon() {
+        /* For a polygon, the control polygon is the shape itself */
+        let b = BezPath::from_svg("M362 714 96 0H10L276 714Z").expect("Failed to parse path");
+        let polygon = b.control_polygon_statistics();
+        let exact = b.green_statistics();
+        assert_eq!(polygon.area(), exact.area());
+        assert_eq!(polygon.center_of_mass(), exact.center_of_mass());
+        assert_eq!(polygon.moment_xxyy, exact.moment_xxyy);
+
+        /* For curves it approximates the shape, unlike the mean of the vertices */
+        let b = BezPath::from_svg("M0 0H100V100Q0 100 0 0Z").expect("Failed to parse path");
+        let polygon = b.control_polygon_statistics();
+        assert_eq!(polygon.area(), b.control_statistics().area());
+        assert_relative_eq!(polygon.area(), 10000.0, epsilon = 1e-9);
+        approx_eq_point(polygon.center_of_mass(), 50.0, 50.0);
+        approx_eq_point(b.control_statistics().center_of_mass(), 40.0, 40.0);
+    }
+
+    #[test]
+    fn test_compute_statistics() {
+        let b = BezPath::from_svg("M362 714 96 0H10L276 714Z").expect("Failed to parse path");
+        let stats = compute_statistics(&b, &StatisticsOptions::default()).unwrap();
+        assert_eq!(stats.covariance(), b.green_statistics().covariance());
+        assert_eq!(stats.slant(), b.green_statistics().slant());
+
+        let control = StatisticsOptions {
+            method: StatisticsMethod::Control,
+            ..Default::default()
+        };
+        let stats = compute_statistics(&b, &control).unwrap();
+        assert_eq!(stats.variance(), b.control_statistics().variance());
+        assert!(matches!(stats.statistics, MethodStatistics::Control(_)));
+
+        let flattened = StatisticsOptions {
+            method: StatisticsMethod::Flattened { tolerance: 0.1 },
+            ..Default::default()
+        };
+        let stats = compute_statistics(&b, &flattened).unwrap();
+        assert_relative_eq!(stats.area(), b.area(), max_relative = 1e-12);
+
+        /* The snapping thresholds are configurable */
+        let strict = StatisticsOptions {
+            correlation_threshold: 0.99,
+            slant_threshold: 0.5,
+            ..Default::default()
+        };
+        let stats = compute_statistics(&b, &strict).unwrap();
+        assert_eq!(stats.correlation(), 0.0);
+        assert_eq!(stats.slant(), 0.0);
+        assert_eq!(stats.slant_with_threshold(0.0), 0.37254901960784315);
+
+        /* Close policy and orientation */
+        let open =
+            BezPath::from_svg("M0 0H300V400H0ZM100 100H200V300").expect("Failed to parse path");
+        let error = StatisticsOptions {
+            close_policy: ClosePolicy::Error,
+            ..Default::default()
+        };
+        assert_eq!(
+            compute_statistics(&open, &error).unwrap_err(),
+            StatisticsError::OpenContour { start_index: 5 }
+        );
+        let outer_only = StatisticsOptions {
+            close_policy: ClosePolicy::IgnoreOpen,
+            method: StatisticsMethod::Control,
+            ..Default::default()
+        };
+        assert_eq!(
+            compute_statistics(&open, &outer_only).unwrap().area(),
+            120000.0
+        );
+
+        /* A contour which draws back to its start point is not open */
+        let returning = BezPath::from_svg("M0 0L10 0L10 10L0 0").expect("Failed to parse path");
+        for close_policy in [ClosePolicy::Error, ClosePolicy::IgnoreOpen] {
+            assert_eq!(
+                returning
+                    .green_statistics_with_policy(close_policy)
+                    .unwrap()
+                    .area(),
+                50.0
+            );
+            for method in [
+                StatisticsMethod::Green,
+                StatisticsMethod::Control,
+                StatisticsMethod::Flattened { tolerance: 0.1 },
+            ] {
+                let options = StatisticsOptions {
+                    close_policy,
+                    method,
+                    ..Default::default()
+                };
+                assert_eq!(
+                    compute_statistics(&returning, &options).unwrap().area(),
+                    50.0
+                );
+            }
+        }
+
+        let clockwise = BezPath::from_svg("M0 0V400H300V0ZM100 100V300H200V100Z")
+            .expect("Failed to parse path");
+        let normalized = StatisticsOptions {
+            orientation: OrientationHandling::Normalized,
+            ..Default::default()
+        };
+        assert_eq!(clockwise.green_statistics().area(), -140000.0);
+        assert_eq!(
+            compute_statistics(&clockwise, &normalized).unwrap().area(),
+            100000.0
+        );
+    }
+
+    #[test]
+    fn test_gradients() {
+        let svg = "M0 0C50 -20 120 30 100 100Q60 140 20 120L-10 60";
+        let b = BezPath::from_svg(svg).expect("Failed to parse path");
+        let gradients = b.green_statistics_gradients();
+        assert_eq!(
+            gradients.statistics.moment_xy,
+            b.green_statistics().moment_xy
+        );
+        assert_eq!(gradients.points.len(), 7);
+        assert_eq!(gradients.points[3].element_index, 1);
+        assert_eq!(gradients.points[3].point, Point::new(100.0, 100.0));
+
+        /* A contour which returns to its start point before closing */
+        let closed =
+            BezPath::from_svg("M30 40L130 40Q150 90 130 140L30 40Z").expect("Failed to parse path");
+        let closed_gradients = closed.green_statistics_gradients();
+        assert_relative_eq!(closed_gradients.points[0].area.x, 0.0, epsilon = 1e-9);
+        assert_relative_eq!(closed_gradients.points[4].area.x, -50.0, epsilon = 1e-9);
+
+        /* Compare against central differences */
+        let h = 1e-4;
+        for b in [b, closed] {
+            let gradients = b.green_statistics_gradients();
+            for (k, gradient) in gradients.points.iter().enumerate() {
+                for (axis, delta) in [Vec2::new(h, 0.0), Vec2::new(0.0, h)].iter().enumerate() {
+                    let nudged = |sign: f64| {
+                        let mut path = BezPath::new();
+                        let mut count = 0;
+                        for el in b.elements() {
+                            let mut nudge = |p: Point| {
+                                count += 1;
+                                if count == k + 1 {
+                                    p + *delta * sign
+                                } else {
+                                    p
+                                }
+                            };
+                            path.push(match *el {
+                                kurbo::PathEl::MoveTo(p) => kurbo::PathEl::MoveTo(nudge(p)),
+                                kurbo::PathEl::LineTo(p) => kurbo::PathEl::LineTo(nudge(p)),
+                                kurbo::PathEl::QuadTo(p1, p2) => {
+                                    kurbo::PathEl::QuadTo(nudge(p1), nudge(p2))
+                                }
+                                kurbo::PathEl::CurveTo(p1, p2, p3) => {
+                                    kurbo::PathEl::CurveTo(nudge(p1), nudge(p2), nudge(p3))
+                                }
+                                kurbo::PathEl::ClosePath => kurbo::PathEl::ClosePath,
+                            });
+                        }
+                        path.green_statistics()
+                    };
+                    let (plus, minus) = (nudged(1.0), nudged(-1.0));
+                    let values = |s: &GreenStatistics| {
+                        [
+                            s.area(),
+                            s.center_of_mass().y,
+                            s.variance().x,
+                            s.covariance(),
+                            s.slant_with_threshold(0.0),
+                        ]
+                    };
+                    let analytic = [
+                        gradient.area,
+                        gradient.center_of_mass_y,
+                        gradient.variance_x,
+                        gradient.covariance,
+                        gradient.slant,
+                    ]
+                    .map(|v| if axis == 0 { v.x } else { v.y });
+                    for ((analytic, plus), minus) in
+                        analytic.iter().zip(values(&plus)).zip(values(&minus))
+                    {
+                        assert_relative_eq!(
+                            *analytic,
+                            (plus - minus) / (2.0 * h),
+                            epsilon = 1e-6,
+                            max_relative = 1e-5
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_invariants() {
+        use std::f64::consts::PI;
+        let disc = GreenStatistics::from_circle(&kurbo::Circle::new((30.0, 40.0), 7.0));
+        let hu = disc.hu_invariants();
+        assert_relative_eq!(hu[0], 1.0 / (2.0 * PI), max_relative = 1e-12);
+        assert_relative_eq!(hu[1], 0.0, epsilon = 1e-12);
+
+        /* Translation, scaling and rotation leave the invariants alone */
+        let b = BezPath::from_svg("M300 -10Q229 -10 173.5 19.0Q118 48 86.5 109.0Q55 170 55 265Q55 364 88.0 426.0Q121 488 177.5 517.0Q234 546 306 546Q347 546 385.0 537.5Q423 529 447 517L420 444Q396 453 364.0 461.0Q332 469 304 469Q146 469 146 266Q146 169 184.5 117.5Q223 66 299 66Q343 66 376.5 75.0Q410 84 438 97V19Q411 5 378.5 -2.5Q346 -10 300 -10Z").expect("Failed to parse path");
+        let stats = b.green_statistics();
+        let moved = stats.transform(
+            kurbo::Affine::translate((-500.0, 90.0))
+                * kurbo::Affine::rotate(0.7)
+                * kurbo::Affine::scale(2.5),
+        );
+        for (a, b) in stats.hu_invariants().iter().zip(moved.hu_invariants()) {
+            assert_relative_eq!(*a, b, max_relative = 1e-9);
+        }
+        assert_relative_eq!(
+            stats.normalized_central_moment(2, 2),
+            stats
+                .transform(kurbo::Affine::scale(0.1))
+                .normalized_central_moment(2, 2),
+            max_relative = 1e-9
+        );
+
+        /* Reflection only changes the sign of the seventh */
+        let mirrored = stats.transform(kurbo::Affine::FLIP_X).hu_invariants();
+        assert_relative_eq!(mirrored[6], -stats.hu_invariants()[6], max_relative = 1e-9);
+        assert_relative_eq!(mirrored[2], stats.hu_invariants()[2], max_relative = 1e-9);
+
+        /* The scale-invariant second moments are the variance over the area */
+        assert_relative_eq!(
+            stats.scale_invariant_variance().y,
+            stats.variance().y / stats.area().abs(),
+            max_relative = 1e-9
+        );
+        assert_relative_eq!(
+            stats.scale_invariant_covariance(),
+            stats.covariance() / stats.area().abs(),
+            max_relative = 1e-9
+        );
+    }
+
+    #[test]
+    fn test_moments() {
+        /* A 30x20 rectangle with its corner at the origin */
+        let rect = BezPath::from_svg("M0 0H30V20H0Z").expect("Failed to parse path");
+        let moments = rect.moments(8);
+        assert_eq!(moments.max_order(), 8);
+        assert_relative_eq!(moments.moment(0, 0), 600.0, max_relative = 1e-12);
+        assert_relative_eq!(
+            moments.moment(6, 0),
+            30.0_f64.powi(7) / 7.0 * 20.0,
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(
+            moments.moment(3, 5),
+            30.0_f64.powi(4) / 4.0 * 20.0_f64.powi(6) / 6.0,
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(
+            moments.central_moment(4, 0),
+            20.0 * 30.0_f64.powi(5) / 80.0,
+            max_relative = 1e-9
+        );
+        assert_relative_eq!(moments.central_moment(1, 0), 0.0, epsilon = 1e-6);
+        assert_relative_eq!(
+            moments.normalized_moment(2, 2),
+            rect.moments(4).normalized_moment(2, 2),
+            max_relative = 1e-12
+        );
+
+        /* GreenStatistics holds the moments up to order four */
+        let b = BezPath::from_svg("M300 -10Q229 -10 173.5 19.0Q118 48 86.5 109.0Q55 170 55 265Q55 364 88.0 426.0Q121 488 177.5 517.0Q234 546 306 546Q347 546 385.0 537.5Q423 529 447 517L420 444Q396 453 364.0 461.0Q332 469 304 469Q146 469 146 266Q146 169 184.5 117.5Q223 66 299 66Q343 66 376.5 75.0Q410 84 438 97V19Q411 5 378.5 -2.5Q346 -10 300 -10Z").expect("Failed to parse path");
+        let stats = b.green_statistics();
+        let general = b.moments(5);
+        assert_relative_eq!(general.moment(1, 1), stats.moment_xy, max_relative = 1e-12);
+        assert_relative_eq!(
+            general.moment(1, 3),
+            stats.moment_xyyy,
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(
+            general.normalized_moment(3, 0),
+            stats.normalized_central_moment(3, 0),
+            max_relative = 1e-6
+        );
+        assert_relative_eq!(
+            b.moments(10).moment(0, 4),
+            stats.moment_yyyy,
+            max_relative = 1e-12
+        );
+        assert_eq!(stats.moments().moment(2, 2), stats.moment_xxyy);
+        assert_relative_eq!(
+            general.green_statistics().variance().x,
+            stats.variance().x,
+            max_relative = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_orthogonal_moments() {
+        use std::f64::consts::PI;
+        /* A rectangle fills its bounding box, so only λ₀₀ is non-zero */
+        let rect = BezPath::from_svg("M0 0V20H30V0Z").expect("Failed to parse path");
+        let legendre = rect.legendre_moments(6).unwrap();
+        assert_relative_eq!(legendre.moment(0, 0), 1.0, max_relative = 1e-12);
+        assert_relative_eq!(legendre.moment(2, 0), 0.0, epsilon = 1e-12);
+        assert_relative_eq!(legendre.moment(3, 3), 0.0, epsilon = 1e-12);
+        assert_relative_eq!(legendre.reconstruction_error(), 0.0, epsilon = 1e-12);
+
+        /* A square's enclosing disk passes through its corners */
+        let square = BezPath::from_svg("M0 0H10V10H0Z").expect("Failed to parse path");
+        let zernike = square.zernike_moments(4).unwrap();
+        assert_eq!(zernike.moments().len(), 9);
+        assert_relative_eq!(
+            zernike.moment(0, 0).unwrap().real,
+            2.0 / PI,
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(
+            zernike.moment(1, 1).unwrap().magnitude(),
+            0.0,
+            epsilon = 1e-12
+        );
+        assert_eq!(zernike.moment(3, 2), None);
+
+        let b = BezPath::from_svg("M300 -10Q229 -10 173.5 19.0Q118 48 86.5 109.0Q55 170 55 265Q55 364 88.0 426.0Q121 488 177.5 517.0Q234 546 306 546Q347 546 385.0 537.5Q423 529 447 517L420 444Q396 453 364.0 461.0Q332 469 304 469Q146 469 146 266Q146 169 184.5 117.5Q223 66 299 66Q343 66 376.5 75.0Q410 84 438 97V19Q411 5 378.5 -2.5Q346 -10 300 -10Z").expect("Failed to parse path");
+        /* Zernike magnitudes are unchanged by rotation */
+        let mut rotated = b.clone();
+        rotated.apply_affine(kurbo::Affine::rotate(1.1) * kurbo::Affine::translate((40.0, 7.0)));
+        let zernike = b.zernike_moments(8).unwrap();
+        for (a, b) in zernike
+            .moments()
+            .iter()
+            .zip(rotated.zernike_moments(8).unwrap().moments())
+        {
+            assert_relative_eq!(
+                a.magnitude(),
+                b.magnitude(),
+                epsilon = 1e-9,
+                max_relative = 1e-6
+            );
+        }
+
+        /* Higher orders reconstruct the shape better */
+        let coarse = b.legendre_moments(2).unwrap().reconstruction_error();
+        let fine = b.legendre_moments(12).unwrap().reconstruction_error();
+        assert!(0.0 < fine && fine < coarse && coarse < 1.0);
+        let coarse = b.zernike_moments(2).unwrap().reconstruction_error();
+        let fine = zernike.reconstruction_error();
+        assert!(0.0 < fine && fine < coarse && coarse < 1.0);
+
+        /* A shape with no height cannot be normalized */
+        let flat = BezPath::from_svg("M0 0H30L10 0Z").expect("Failed to parse path");
+        assert_eq!(
+            flat.legendre_moments(4),
+            Err(StatisticsError::DegenerateArea)
+        );
+        assert_eq!(
+            BezPath::new().zernike_moments(4),
+            Err(StatisticsError::DegenerateArea)
+        );
+
+        /* Beyond the highest supported order the moments are mostly rounding error */
+        assert!(b.zernike_moments(MAX_ORTHOGONAL_ORDER).is_ok());
+        assert_eq!(
+            b.zernike_moments(200),
+            Err(StatisticsError::UnsupportedOrder { order: 200 })
+        );
+        assert_eq!(
+            b.legendre_moments(MAX_ORTHOGONAL_ORDER + 1),
+            Err(StatisticsError::UnsupportedOrder {
+                order: MAX_ORTHOGONAL_ORDER + 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_shape_distance() {
+        let options = DistanceOptions::default();
+        let square = BezPath::from_svg("M0 0H10V10H0Z").expect("Failed to parse path");
+        let moved = BezPath::from_svg("M500 300V330H530V300Z").expect("Failed to parse path");
+        let distance = shape_distance(&square, &moved, &options).unwrap();
+        assert_eq!(distance.components.len(), 12);
+        assert_relative_eq!(distance.distance, 0.0, epsilon = 1e-9);
+        assert_relative_eq!(distance.similarity(), 1.0, epsilon = 1e-9);
+
+        let c = BezPath::from_svg("M300 -10Q229 -10 173.5 19.0Q118 48 86.5 109.0Q55 170 55 265Q55 364 88.0 426.0Q121 488 177.5 517.0Q234 546 306 546Q347 546 385.0 537.5Q423 529 447 517L420 444Q396 453 364.0 461.0Q332 469 304 469Q146 469 146 266Q146 169 184.5 117.5Q223 66 299 66Q343 66 376.5 75.0Q410 84 438 97V19Q411 5 378.5 -2.5Q346 -10 300 -10Z").expect("Failed to parse path");
+        /* Mirroring flips the sign of the moments which are odd in x */
+        let mut mirrored = c.clone();
+        mirrored.apply_affine(kurbo::Affine::FLIP_X);
+        let distance = shape_distance(&c, &mirrored, &options).unwrap();
+        assert!(distance.distance > 0.01);
+        for component in &distance.components {
+            let sign = if component.p % 2 == 1 { -1.0 } else { 1.0 };
+            assert_relative_eq!(component.a, sign * component.b, epsilon = 1e-12);
+        }
+        let largest = distance.largest_component().unwrap();
+        assert_eq!(largest.p % 2, 1);
+
+        /* Rotation only matters when the axes are not aligned */
+        let mut rotated = c.clone();
+        rotated.apply_affine(kurbo::Affine::rotate(2.5) * kurbo::Affine::scale(0.3));
+        let distance = shape_distance(&c, &rotated, &options).unwrap();
+        assert!(distance.distance > 0.01);
+        let aligned = DistanceOptions {
+            align_axes: true,
+            ..options
+        };
+        let distance = shape_distance(&c, &rotated, &aligned).unwrap();
+        assert_relative_eq!(distance.distance, 0.0, epsilon = 1e-9);
+        /* A shape with no line of symmetry is not a rotation of its mirror image */
+        let ell = BezPath::from_svg("M0 0H30V10H10V50H0Z").expect("Failed to parse path");
+        let mut mirrored = ell.clone();
+        mirrored.apply_affine(kurbo::Affine::FLIP_X);
+        let distance = shape_distance(&ell, &mirrored, &aligned).unwrap();
+        assert!(distance.distance > 0.01);
+
+        /* Aligning the axes does not depend on the direction of the path */
+        for shape in [&ell, &c] {
+            let reversed = shape.reverse_subpaths();
+            let distance = shape_distance(shape, &reversed, &aligned).unwrap();
+            assert_relative_eq!(distance.distance, 0.0, epsilon = 1e-9);
+        }
+
+        assert_eq!(
+            shape_distance(&c, &BezPath::new(), &options),
+            Err(StatisticsError::DegenerateArea)
+        );
+        let options = DistanceOptions {
+            max_order: 5,
+            ..options
+        };
+        assert_eq!(
+            shape_distance(&square, &moved, &options),
+            Err(StatisticsError::UnsupportedOrder { order: 5 })
+        );
+    }
+
+    #[test]
+    fn test_feature_vector() {
+        let b = BezPath::from_svg("M300 -10Q229 -10 173.5 19.0Q118 48 86.5 109.0Q55 170 55 265Q55 364 88.0 426.0Q121 488 177.5 517.0Q234 546 306 546Q347 546 385.0 537.5Q423 529 447 517L420 444Q396 453 364.0 461.0Q332 469 304 469Q146 469 146 266Q146 169 184.5 117.5Q223 66 299 66Q343 66 376.5 75.0Q410 84 438 97V19Q411 5 378.5 -2.5Q346 -10 300 -10Z").expect("Failed to parse path");
+        let green = b.green_statistics();
+        let boundary = b.boundary_statistics(1e-9);
+        let features = FeatureVector::new(&green).build();
+        assert_eq!(features[0], green.area());
+        assert_eq!(features[2], green.center_of_mass().y);
+        assert_eq!(features[7], green.slant());
+        assert!(features[12..].iter().all(|f| f.is_nan()));
+
+        let features = FeatureVector::new(&green)
+            .hu_invariants(&green)
+            .boundary(&boundary)
+            .contour_count(1)
+            .build();
+        assert!(features.iter().all(|f| f.is_finite()));
+        assert_eq!(features[12..19], green.hu_invariants());
+        assert_eq!(features[19], boundary.length);
+        assert_eq!(features[23], 1.0);
+        assert_eq!(FEATURE_NAMES[23], "contour_count");
+
+        /* Normalizing to ems scales lengths but leaves ratios alone */
+        let em = FeatureVector::new(&green)
+            .boundary(&boundary)
+            .normalization(FeatureNormalization::Em(1000.0))
+            .build();
+        assert_relative_eq!(em[0], features[0] / 1e6, max_relative = 1e-12);
+        assert_relative_eq!(em[1], features[1] / 1e3, max_relative = 1e-12);
+        assert_relative_eq!(em[4], features[4] / 1e6, max_relative = 1e-12);
+        assert_relative_eq!(em[19], features[19] / 1e3, max_relative = 1e-12);
+        assert_eq!(em[6], features[6]);
+        assert_eq!(em[9], features[9]);
+
+        /* A shape and a scaled copy look the same at unit variance */
+        let mut scaled = b.clone();
+        scaled.apply_affine(kurbo::Affine::scale(3.0));
+        let scaled = scaled.green_statistics();
+        let unit = FeatureVector::new(&green)
+            .normalization(FeatureNormalization::UnitVariance)
+            .build();
+        assert_relative_eq!((unit[3] + unit[4]) / 2.0, 1.0, max_relative = 1e-12);
+        let scaled = FeatureVector::new(&scaled)
+            .normalization(FeatureNormalization::UnitVariance)
+            .build();
+        for (a, b) in unit[..12].iter().zip(&scaled[..12]) {
+            assert_relative_eq!(a, b, epsilon = 1e-9, max_relative = 1e-9);
+        }
+    }
+
     #[test]
     fn test_control_c() {
         /* Noto Sans Regular 'c', i.e. a single quad path */
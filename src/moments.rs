@@ -0,0 +1,168 @@
+use kurbo::{BezPath, PathEl, PathSeg};
+
+use crate::contour::split_contours;
+use crate::poly::{moment_quadrature, power_basis, segment_moments};
+use crate::{ComputeMoments, GreenStatistics};
+
+/// Area moments of a path up to an arbitrary order
+///
+/// Entry `(p, q)` is the integral of `x^p y^q` over the (signed) area of the path,
+/// for every `p + q` up to [Moments::max_order]. [GreenStatistics] holds the same
+/// moments up to order four.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Moments {
+    max_order: usize,
+    /// The moments of each order in turn, each running from `(n, 0)` to `(0, n)`
+    values: Vec<f64>,
+}
+
+impl Moments {
+    /// Create a table of zero moments
+    pub fn new(max_order: usize) -> Self {
+        Moments {
+            max_order,
+            values: vec![0.0; (max_order + 1) * (max_order + 2) / 2],
+        }
+    }
+
+    /// The highest order of moment held
+    pub fn max_order(&self) -> usize {
+        self.max_order
+    }
+
+    /// The index of `(p, q)` in a table stored by order, as [Moments] is
+    pub(crate) fn index(p: usize, q: usize) -> usize {
+        let order = p + q;
+        order * (order + 1) / 2 + q
+    }
+
+    fn checked_index(&self, p: usize, q: usize) -> usize {
+        assert!(
+            p + q <= self.max_order,
+            "moments of order {} are not held",
+            p + q
+        );
+        Moments::index(p, q)
+    }
+
+    /// Find the raw moment M_pq, the integral of `x^p y^q` over the area
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p + q` is more than [Moments::max_order].
+    pub fn moment(&self, p: usize, q: usize) -> f64 {
+        self.values[self.checked_index(p, q)]
+    }
+
+    /// Find the central moment μ_pq, the moment about the center of mass
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p + q` is more than [Moments::max_order].
+    pub fn central_moment(&self, p: usize, q: usize) -> f64 {
+        self.checked_index(p, q);
+        let area = self.moment(0, 0);
+        let (cx, cy) = (self.moment(1, 0) / area, self.moment(0, 1) / area);
+        // Expand (x - cx)^p (y - cy)^q binomially
+        let mut sum = 0.0;
+        for i in 0..=p {
+            for j in 0..=q {
+                sum += binomial(p, i)
+                    * binomial(q, j)
+                    * (-cx).powi((p - i) as i32)
+                    * (-cy).powi((q - j) as i32)
+                    * self.moment(i, j);
+            }
+        }
+        sum
+    }
+
+    /// Find the normalized central moment η_pq
+    ///
+    /// As for [GreenStatistics::normalized_central_moment], this is invariant under
+    /// translation and uniform scaling, and does not depend on the direction of
+    /// the path.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p + q` is more than [Moments::max_order].
+    pub fn normalized_moment(&self, p: usize, q: usize) -> f64 {
+        let area = self.moment(0, 0);
+        self.central_moment(p, q) / area / area.abs().powf((p + q) as f64 / 2.0)
+    }
+
+    /// Convert to [GreenStatistics], dropping any moments above order four
+    pub fn green_statistics(&self) -> GreenStatistics {
+        let mut table = [[0.0; 5]; 5];
+        for (p, row) in table.iter_mut().enumerate() {
+            for (q, moment) in row.iter_mut().enumerate().take(5 - p) {
+                if p + q <= self.max_order {
+                    *moment = self.moment(p, q);
+                }
+            }
+        }
+        GreenStatistics::from_table(&table)
+    }
+
+    /// Add the moments of a single segment, using the quadrature `nodes` from
+    /// [moment_quadrature] for this table's order
+    fn add_segment(&mut self, seg: PathSeg, nodes: &[(f64, f64)]) {
+        let (x, y) = match seg {
+            PathSeg::Line(l) => (
+                power_basis(&[l.p0.x, l.p1.x]),
+                power_basis(&[l.p0.y, l.p1.y]),
+            ),
+            PathSeg::Quad(q) => (
+                power_basis(&[q.p0.x, q.p1.x, q.p2.x]),
+                power_basis(&[q.p0.y, q.p1.y, q.p2.y]),
+            ),
+            PathSeg::Cubic(c) => (
+                power_basis(&[c.p0.x, c.p1.x, c.p2.x, c.p3.x]),
+                power_basis(&[c.p0.y, c.p1.y, c.p2.y, c.p3.y]),
+            ),
+        };
+        segment_moments(&x, &y, 0..=self.max_order, nodes, |p, q, moment| {
+            let index = self.checked_index(p, q);
+            self.values[index] += moment;
+        });
+    }
+}
+
+impl GreenStatistics {
+    /// The moments up to order four, as a [Moments] table
+    pub fn moments(&self) -> Moments {
+        let table = self.moment_table();
+        let mut moments = Moments::new(4);
+        for order in 0..=4 {
+            for q in 0..=order {
+                let index = moments.checked_index(order - q, q);
+                moments.values[index] = table[order - q][q];
+            }
+        }
+        moments
+    }
+}
+
+pub(crate) fn binomial(n: usize, k: usize) -> f64 {
+    (0..k).fold(1.0, |c, i| c * (n - i) as f64 / (i + 1) as f64)
+}
+
+impl<'a, T: 'a> ComputeMoments<'a> for T
+where
+    &'a T: IntoIterator<Item = PathEl>,
+{
+    fn moments(&'a self, max_order: usize) -> Moments {
+        let mut moments = Moments::new(max_order);
+        let nodes = moment_quadrature(max_order);
+        for contour in split_contours(self) {
+            let mut path: BezPath = contour.path;
+            if !contour.closed {
+                path.close_path();
+            }
+            for seg in path.segments() {
+                moments.add_segment(seg, &nodes);
+            }
+        }
+        moments
+    }
+}
@@ -0,0 +1,205 @@
+use kurbo::{BezPath, PathEl, Point, Vec2};
+
+use crate::contour::{find_holes, split_contours};
+use crate::{
+    ClosePolicy, ComputeControlStatistics, ComputeFlattenedStatistics, ComputeGreenStatistics,
+    ControlStatistics, CurveStatistics, GreenStatistics, StatisticsError, DEFAULT_SNAP_THRESHOLD,
+};
+
+/// The method used by [compute_statistics]
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub enum StatisticsMethod {
+    /// Integrate the area exactly, as [ComputeGreenStatistics::green_statistics]
+    #[default]
+    Green,
+    /// Use the control points, as [ComputeControlStatistics::control_statistics]
+    Control,
+    /// Flatten the path first, as [ComputeFlattenedStatistics::flattened_statistics]
+    Flattened {
+        /// The flattening tolerance
+        tolerance: f64,
+    },
+}
+
+/// How [compute_statistics] treats the direction of contours
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum OrientationHandling {
+    /// Use the contours as drawn, so that the sign of the area follows their direction
+    #[default]
+    AsDrawn,
+    /// Orient outer contours counter-clockwise and holes clockwise, as in
+    /// [ComputeGreenStatistics::green_statistics_normalized]
+    Normalized,
+}
+
+/// Options for [compute_statistics]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct StatisticsOptions {
+    pub method: StatisticsMethod,
+    pub close_policy: ClosePolicy,
+    pub orientation: OrientationHandling,
+    /// Correlations no larger than this in magnitude are reported as zero
+    pub correlation_threshold: f64,
+    /// Slants no larger than this in magnitude are reported as zero
+    pub slant_threshold: f64,
+}
+
+impl Default for StatisticsOptions {
+    fn default() -> Self {
+        StatisticsOptions {
+            method: StatisticsMethod::default(),
+            close_policy: ClosePolicy::default(),
+            orientation: OrientationHandling::default(),
+            correlation_threshold: DEFAULT_SNAP_THRESHOLD,
+            slant_threshold: DEFAULT_SNAP_THRESHOLD,
+        }
+    }
+}
+
+/// The statistics computed by one of the methods
+#[derive(Debug, Clone)]
+pub enum MethodStatistics {
+    /// Computed by [StatisticsMethod::Green] or [StatisticsMethod::Flattened]
+    Green(GreenStatistics),
+    /// Computed by [StatisticsMethod::Control]
+    Control(ControlStatistics),
+}
+
+impl MethodStatistics {
+    fn as_dyn(&self) -> &dyn CurveStatistics {
+        match self {
+            MethodStatistics::Green(statistics) => statistics,
+            MethodStatistics::Control(statistics) => statistics,
+        }
+    }
+}
+
+/// Statistics returned by [compute_statistics]
+///
+/// These report the correlation and slant using the thresholds given in the
+/// [StatisticsOptions].
+#[derive(Debug, Clone)]
+pub struct Statistics {
+    pub statistics: MethodStatistics,
+    pub correlation_threshold: f64,
+    pub slant_threshold: f64,
+}
+
+impl CurveStatistics for Statistics {
+    fn area(&self) -> f64 {
+        self.statistics.as_dyn().area()
+    }
+
+    fn center_of_mass(&self) -> Point {
+        self.statistics.as_dyn().center_of_mass()
+    }
+
+    fn variance(&self) -> Vec2 {
+        self.statistics.as_dyn().variance()
+    }
+
+    fn covariance(&self) -> f64 {
+        self.statistics.as_dyn().covariance()
+    }
+
+    fn skewness(&self) -> Vec2 {
+        self.statistics.as_dyn().skewness()
+    }
+
+    fn kurtosis(&self) -> Vec2 {
+        self.statistics.as_dyn().kurtosis()
+    }
+
+    fn correlation(&self) -> f64 {
+        self.correlation_with_threshold(self.correlation_threshold)
+    }
+
+    fn slant(&self) -> f64 {
+        self.slant_with_threshold(self.slant_threshold)
+    }
+
+    fn check(&self) -> Result<(), StatisticsError> {
+        self.statistics.as_dyn().check()
+    }
+}
+
+/// Compute statistics for a path, choosing the method and its treatment of the path
+///
+/// With the default options this is equivalent to
+/// [ComputeGreenStatistics::green_statistics]. The only error which can occur is
+/// [StatisticsError::OpenContour], with [ClosePolicy::Error].
+pub fn compute_statistics<'a, T: 'a>(
+    path: &'a T,
+    options: &StatisticsOptions,
+) -> Result<Statistics, StatisticsError>
+where
+    &'a T: IntoIterator<Item = PathEl>,
+{
+    // The Green method applies the close policy itself as it integrates
+    let statistics = if options.orientation == OrientationHandling::AsDrawn
+        && (options.close_policy == ClosePolicy::Implicit
+            || options.method == StatisticsMethod::Green)
+    {
+        method_statistics(path, options)?
+    } else {
+        let prepared = prepare(path, options)?;
+        method_statistics::<BezPath>(&prepared, options)?
+    };
+    Ok(Statistics {
+        statistics,
+        correlation_threshold: options.correlation_threshold,
+        slant_threshold: options.slant_threshold,
+    })
+}
+
+fn method_statistics<'a, T: 'a>(
+    path: &'a T,
+    options: &StatisticsOptions,
+) -> Result<MethodStatistics, StatisticsError>
+where
+    &'a T: IntoIterator<Item = PathEl>,
+{
+    Ok(match options.method {
+        StatisticsMethod::Green => {
+            MethodStatistics::Green(path.green_statistics_with_policy(options.close_policy)?)
+        }
+        StatisticsMethod::Control => MethodStatistics::Control(path.control_statistics()),
+        StatisticsMethod::Flattened { tolerance } => {
+            MethodStatistics::Green(path.flattened_statistics(tolerance))
+        }
+    })
+}
+
+/// Apply the close policy and orientation handling, giving a new path
+fn prepare(
+    elements: impl IntoIterator<Item = PathEl>,
+    options: &StatisticsOptions,
+) -> Result<BezPath, StatisticsError> {
+    let mut contours = split_contours(elements);
+    match options.close_policy {
+        ClosePolicy::Implicit => {}
+        ClosePolicy::IgnoreOpen => contours.retain(|contour| !contour.is_open()),
+        ClosePolicy::Error => {
+            if let Some(open) = contours.iter().find(|contour| contour.is_open()) {
+                return Err(StatisticsError::OpenContour {
+                    start_index: open.start_index,
+                });
+            }
+        }
+    }
+    let holes = match options.orientation {
+        OrientationHandling::AsDrawn => None,
+        OrientationHandling::Normalized => Some(find_holes(&contours)),
+    };
+    let mut path = BezPath::new();
+    for (i, contour) in contours.into_iter().enumerate() {
+        match &holes {
+            // Outer contours should have a positive area, holes a negative one
+            Some(holes) if (contour.path.green_statistics().area() < 0.0) != holes[i] => {
+                path.extend(contour.path.reverse_subpaths())
+            }
+            _ => path.extend(contour.path),
+        }
+    }
+    Ok(path)
+}
@@ -0,0 +1,277 @@
+use std::f64::consts::PI;
+
+use kurbo::{Affine, BezPath, PathEl, Shape};
+
+use crate::moments::binomial;
+use crate::{
+    ComputeGreenStatistics, ComputeMoments, ComputeOrthogonalMoments, CurveStatistics, Moments,
+    StatisticsError,
+};
+
+/// The highest order of orthogonal moment which can be computed
+///
+/// Orthogonal moments are found by expanding their polynomials into raw moments,
+/// which cancel one another more and more as the order rises; beyond this order
+/// the cancellation leaves little but rounding error.
+pub const MAX_ORTHOGONAL_ORDER: usize = 30;
+
+/// Legendre moments of a path, normalized to its bounding box
+///
+/// The path is mapped so that its bounding box becomes the square `-1..1` in
+/// each axis, and its indicator function (one inside the shape, zero outside) is
+/// projected onto the products of Legendre polynomials `P_m(x) P_n(y)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LegendreMoments {
+    max_order: usize,
+    /// The moments of each order `m + n` in turn, each running from `(m + n, 0)` to `(0, m + n)`
+    values: Vec<f64>,
+    /// The area of the normalized shape
+    area: f64,
+}
+
+impl LegendreMoments {
+    /// The highest order `m + n` of moment held
+    pub fn max_order(&self) -> usize {
+        self.max_order
+    }
+
+    /// Find the Legendre moment λ_mn
+    ///
+    /// # Panics
+    ///
+    /// Panics if `m + n` is more than [LegendreMoments::max_order].
+    pub fn moment(&self, m: usize, n: usize) -> f64 {
+        assert!(
+            m + n <= self.max_order,
+            "moments of order {} are not held",
+            m + n
+        );
+        self.values[Moments::index(m, n)]
+    }
+
+    /// The fraction of the shape lost when reconstructing it from these moments
+    ///
+    /// This is the squared L² distance between the indicator function and its
+    /// reconstruction, relative to the area of the shape. It is zero for a perfect
+    /// reconstruction and one when the moments capture nothing.
+    pub fn reconstruction_error(&self) -> f64 {
+        let mut captured = 0.0;
+        for order in 0..=self.max_order {
+            for n in 0..=order {
+                let m = order - n;
+                // ∫ P_m(x)² dx = 2 / (2m + 1)
+                let norm = 4.0 / ((2 * m + 1) * (2 * n + 1)) as f64;
+                captured += self.moment(m, n).powi(2) * norm;
+            }
+        }
+        1.0 - captured / self.area
+    }
+}
+
+/// A single Zernike moment Z_nm
+///
+/// Moments with negative `m` are the complex conjugates of those with positive
+/// `m`, so only `m >= 0` is stored.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ZernikeMoment {
+    /// The radial order
+    pub n: usize,
+    /// The angular repetition; `n - m` is always even
+    pub m: usize,
+    pub real: f64,
+    pub imaginary: f64,
+}
+
+impl ZernikeMoment {
+    /// The magnitude of the moment, which does not change when the shape is rotated
+    pub fn magnitude(&self) -> f64 {
+        self.real.hypot(self.imaginary)
+    }
+}
+
+/// Zernike moments of a path, normalized to an enclosing disk
+///
+/// The path is translated so that its center of mass is at the origin, and scaled
+/// so that the unit disk encloses all of its control points (and so the whole
+/// path). Its indicator function is then projected onto the Zernike polynomials.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZernikeMoments {
+    max_order: usize,
+    /// Ordered by `n`, then `m`
+    moments: Vec<ZernikeMoment>,
+    /// The area of the normalized shape
+    area: f64,
+}
+
+impl ZernikeMoments {
+    /// The highest radial order `n` held
+    pub fn max_order(&self) -> usize {
+        self.max_order
+    }
+
+    /// All of the moments, ordered by `n` and then `m`
+    pub fn moments(&self) -> &[ZernikeMoment] {
+        &self.moments
+    }
+
+    /// Find the moment Z_nm
+    ///
+    /// Returns `None` if `n` is more than [ZernikeMoments::max_order], or if
+    /// `n - m` is odd or negative.
+    pub fn moment(&self, n: usize, m: usize) -> Option<ZernikeMoment> {
+        self.moments
+            .iter()
+            .find(|moment| moment.n == n && moment.m == m)
+            .copied()
+    }
+
+    /// The fraction of the shape lost when reconstructing it from these moments
+    ///
+    /// As for [LegendreMoments::reconstruction_error], this is zero for a perfect
+    /// reconstruction and one when the moments capture nothing.
+    pub fn reconstruction_error(&self) -> f64 {
+        let captured: f64 = self
+            .moments
+            .iter()
+            .map(|moment| {
+                // Count the conjugate moment with negative m too
+                let count = if moment.m == 0 { 1.0 } else { 2.0 };
+                count * moment.magnitude().powi(2) * PI / (moment.n + 1) as f64
+            })
+            .sum();
+        1.0 - captured / self.area
+    }
+}
+
+/// The power basis coefficients of the Legendre polynomials up to degree `n`
+fn legendre_polynomials(n: usize) -> Vec<Vec<f64>> {
+    let mut polys = vec![vec![1.0], vec![0.0, 1.0]];
+    for k in 1..n {
+        let mut next = vec![0.0; k + 2];
+        for (i, c) in polys[k].iter().enumerate() {
+            next[i + 1] += (2 * k + 1) as f64 * c / (k + 1) as f64;
+        }
+        for (i, c) in polys[k - 1].iter().enumerate() {
+            next[i] -= k as f64 * c / (k + 1) as f64;
+        }
+        polys.push(next);
+    }
+    polys.truncate(n + 1);
+    polys
+}
+
+fn factorial(n: usize) -> f64 {
+    (1..=n).map(|i| i as f64).product()
+}
+
+/// Integrate the conjugate Zernike polynomial V*_nm against the moments
+///
+/// `R_nm(ρ) e^(-imθ)` is a sum of terms `ρ^(k-m) (x - iy)^m`, each a polynomial in
+/// `x` and `y` whose integral is a combination of raw moments.
+fn zernike_integral(moments: &Moments, n: usize, m: usize) -> (f64, f64) {
+    let (mut real, mut imaginary) = (0.0, 0.0);
+    for s in 0..=(n - m) / 2 {
+        let sign = if s % 2 == 0 { 1.0 } else { -1.0 };
+        let coeff = sign * factorial(n - s)
+            / (factorial(s) * factorial((n + m) / 2 - s) * factorial((n - m) / 2 - s));
+        // ρ^(n-2s) (x - iy)^m = (x² + y²)^j (x - iy)^m
+        let j = (n - m) / 2 - s;
+        for a in 0..=j {
+            // (x² + y²)^j = Σ C(j, a) x^(2a) y^(2(j-a))
+            let radial = binomial(j, a);
+            for b in 0..=m {
+                // (x - iy)^m = Σ C(m, b) x^(m-b) (-i)^b y^b
+                let term = coeff * radial * binomial(m, b);
+                let moment = moments.moment(2 * a + m - b, 2 * (j - a) + b);
+                match b % 4 {
+                    0 => real += term * moment,
+                    1 => imaginary -= term * moment,
+                    2 => real -= term * moment,
+                    _ => imaginary += term * moment,
+                }
+            }
+        }
+    }
+    (real, imaginary)
+}
+
+impl<'a, T: 'a> ComputeOrthogonalMoments<'a> for T
+where
+    &'a T: IntoIterator<Item = PathEl>,
+{
+    fn legendre_moments(&'a self, max_order: usize) -> Result<LegendreMoments, StatisticsError> {
+        if max_order > MAX_ORTHOGONAL_ORDER {
+            return Err(StatisticsError::UnsupportedOrder { order: max_order });
+        }
+        let mut path: BezPath = self.into_iter().collect();
+        // A shape with area has a bounding box with non-zero width and height
+        path.green_statistics().check()?;
+        let bbox = path.bounding_box();
+        path.apply_affine(
+            Affine::scale_non_uniform(2.0 / bbox.width(), 2.0 / bbox.height())
+                * Affine::translate(-bbox.center().to_vec2()),
+        );
+        let moments = path.moments(max_order);
+        // Describe the shape itself, whichever direction it is drawn in
+        let sign = moments.moment(0, 0).signum();
+        let polys = legendre_polynomials(max_order);
+        let mut values = vec![];
+        for order in 0..=max_order {
+            for n in 0..=order {
+                let m = order - n;
+                let mut integral = 0.0;
+                for (k, a) in polys[m].iter().enumerate() {
+                    for (l, b) in polys[n].iter().enumerate() {
+                        integral += a * b * moments.moment(k, l);
+                    }
+                }
+                values.push(sign * integral * ((2 * m + 1) * (2 * n + 1)) as f64 / 4.0);
+            }
+        }
+        Ok(LegendreMoments {
+            max_order,
+            values,
+            area: moments.moment(0, 0).abs(),
+        })
+    }
+
+    fn zernike_moments(&'a self, max_order: usize) -> Result<ZernikeMoments, StatisticsError> {
+        if max_order > MAX_ORTHOGONAL_ORDER {
+            return Err(StatisticsError::UnsupportedOrder { order: max_order });
+        }
+        let mut path: BezPath = self.into_iter().collect();
+        let center = path.green_statistics().try_center_of_mass()?;
+        let radius = path
+            .elements()
+            .iter()
+            .flat_map(|el| match *el {
+                PathEl::MoveTo(p) | PathEl::LineTo(p) => vec![p],
+                PathEl::QuadTo(p1, p2) => vec![p1, p2],
+                PathEl::CurveTo(p1, p2, p3) => vec![p1, p2, p3],
+                PathEl::ClosePath => vec![],
+            })
+            .map(|p| p.distance(center))
+            .fold(0.0, f64::max);
+        path.apply_affine(Affine::scale(1.0 / radius) * Affine::translate(-center.to_vec2()));
+        let moments = path.moments(max_order);
+        let sign = moments.moment(0, 0).signum();
+        let mut zernike = vec![];
+        for n in 0..=max_order {
+            for m in (n % 2..=n).step_by(2) {
+                let (real, imaginary) = zernike_integral(&moments, n, m);
+                let scale = sign * (n + 1) as f64 / PI;
+                zernike.push(ZernikeMoment {
+                    n,
+                    m,
+                    real: real * scale,
+                    imaginary: imaginary * scale,
+                });
+            }
+        }
+        Ok(ZernikeMoments {
+            max_order,
+            moments: zernike,
+            area: moments.moment(0, 0).abs(),
+        })
+    }
+}
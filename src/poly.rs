@@ -0,0 +1,142 @@
+use std::borrow::Cow;
+use std::f64::consts::PI;
+use std::ops::RangeInclusive;
+
+use crate::Scalar;
+
+/// Multiply two polynomials in power basis
+pub(crate) fn poly_mul<S: Scalar>(a: &[S], b: &[S]) -> Vec<S> {
+    let mut out = vec![S::default(); a.len() + b.len() - 1];
+    for (i, ai) in a.iter().enumerate() {
+        for (j, bj) in b.iter().enumerate() {
+            out[i + j] += *ai * *bj;
+        }
+    }
+    out
+}
+
+/// Integrate a polynomial in power basis over `0..1`
+pub(crate) fn poly_integrate_unit<S: Scalar>(a: &[S]) -> S {
+    a.iter().enumerate().fold(S::default(), |sum, (i, ai)| {
+        sum + *ai / S::from_f64((i + 1) as f64)
+    })
+}
+
+/// Convert the control values of a Bézier curve of degree up to three to power
+/// basis coefficients, lowest order first
+///
+/// Coefficients beyond the degree of the curve are zero.
+pub(crate) fn power_basis<S: Scalar>(control: &[S]) -> [S; 4] {
+    let c = S::from_f64;
+    let zero = S::default();
+    match *control {
+        [p0, p1] => [p0, p1 - p0, zero, zero],
+        [p0, p1, p2] => [p0, c(2.0) * (p1 - p0), p0 - c(2.0) * p1 + p2, zero],
+        [p0, p1, p2, p3] => [
+            p0,
+            c(3.0) * (p1 - p0),
+            c(3.0) * (p0 - c(2.0) * p1 + p2),
+            p3 - c(3.0) * p2 + c(3.0) * p1 - p0,
+        ],
+        _ => unreachable!("Bézier curves have two to four control points"),
+    }
+}
+
+/// Nodes and weights of nine-point Gauss-Legendre quadrature on `0..1`
+///
+/// This integrates polynomials of degree up to 17 exactly, which covers
+/// `x^p y^(q+1) dx` for a cubic segment with `p + q` up to four.
+const GAUSS_LEGENDRE: [(f64, f64); 9] = [
+    (0.015919880246186954, 0.040637194180787206),
+    (0.0819844463366821, 0.0903240803474287),
+    (0.1933142836497048, 0.13030534820146772),
+    (0.33787328829809554, 0.15617353852000143),
+    (0.5, 0.1651196775006299),
+    (0.6621267117019045, 0.15617353852000143),
+    (0.8066857163502952, 0.13030534820146772),
+    (0.9180155536633179, 0.0903240803474287),
+    (0.984080119753813, 0.040637194180787206),
+];
+
+/// Gauss-Legendre nodes and weights on `0..1` which integrate the moments of a
+/// cubic segment exactly, for every order up to `max_order`
+///
+/// Up to order four this is the precomputed nine-point rule; beyond that the
+/// nodes are found by Newton's method on the Legendre polynomial.
+pub(crate) fn moment_quadrature(max_order: usize) -> Cow<'static, [(f64, f64)]> {
+    if max_order <= 4 {
+        return Cow::Borrowed(&GAUSS_LEGENDRE);
+    }
+    // The integrand has degree 3 * max_order + 5
+    let n = (3 * max_order + 7) / 2;
+    let nodes = (0..n)
+        .map(|i| {
+            let mut z = (PI * (i as f64 + 0.75) / (n as f64 + 0.5)).cos();
+            let mut derivative = 1.0;
+            for _ in 0..100 {
+                // Evaluate P_n(z) by the three-term recurrence
+                let (mut p, mut previous) = (1.0, 0.0);
+                for j in 0..n {
+                    let j = j as f64;
+                    (p, previous) = (((2.0 * j + 1.0) * z * p - j * previous) / (j + 1.0), p);
+                }
+                derivative = n as f64 * (z * p - previous) / (z * z - 1.0);
+                let step = p / derivative;
+                z -= step;
+                if step.abs() <= 1e-15 {
+                    break;
+                }
+            }
+            (
+                (1.0 - z) / 2.0,
+                1.0 / ((1.0 - z * z) * derivative * derivative),
+            )
+        })
+        .collect();
+    Cow::Owned(nodes)
+}
+
+/// Integrate the area moments of a polynomial segment
+///
+/// The segment is given as polynomials in `t` (power basis coefficients, lowest
+/// order first) of degree at most three. For every `p + q` in `orders`, the
+/// moment is the integral of `-x^p y^(q+1) / (q+1) dx` over `t` in `0..1`
+/// (Green's theorem). The integrand is a polynomial, so quadrature with the
+/// `nodes` of [moment_quadrature] gives it exactly without forming any
+/// intermediate polynomials. Each node's contribution to a moment is passed to
+/// `add` along with `p` and `q`.
+pub(crate) fn segment_moments<S: Scalar>(
+    x: &[S],
+    y: &[S],
+    orders: RangeInclusive<usize>,
+    nodes: &[(f64, f64)],
+    mut add: impl FnMut(usize, usize, S),
+) {
+    debug_assert!(x.len() <= 4 && y.len() <= 4);
+    let c = S::from_f64;
+    let eval = |poly: &[S], t: S| {
+        poly.iter()
+            .rev()
+            .fold(S::default(), |sum, coeff| sum * t + *coeff)
+    };
+    let mut dx = [S::default(); 3];
+    for (j, coeff) in x.iter().enumerate().skip(1) {
+        dx[j - 1] = *coeff * c(j as f64);
+    }
+    let (min_order, max_order) = (*orders.start(), *orders.end());
+    for &(t, weight) in nodes {
+        let t = c(t);
+        let (x_t, y_t) = (eval(x, t), eval(y, t));
+        let dx_t = -eval(&dx, t) * c(weight);
+        let mut x_power = dx_t;
+        for p in 0..=max_order {
+            let first = min_order.saturating_sub(p);
+            let mut y_power = (0..=first).fold(x_power, |power, _| power * y_t);
+            for q in first..=max_order - p {
+                add(p, q, y_power * c(1.0 / (q + 1) as f64));
+                y_power = y_power * y_t;
+            }
+            x_power = x_power * x_t;
+        }
+    }
+}
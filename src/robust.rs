@@ -0,0 +1,134 @@
+use kurbo::{Affine, PathEl, Point, Vec2};
+
+use crate::contour::split_contours;
+use crate::green::MomentTable;
+use crate::{ComputeRobustStatistics, CurveStatistics, GreenStatistics};
+
+/// Statistics held as moments about a local origin
+///
+/// The origin is placed at the center of mass, so the variance and higher
+/// moments are read directly from the local moments instead of being found as
+/// the small difference of two large numbers.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct RobustStatistics {
+    /// The point about which the moments are taken
+    pub origin: Point,
+    /// The moments relative to the origin
+    pub local: GreenStatistics,
+}
+
+impl RobustStatistics {
+    /// The moments relative to the coordinate origin
+    ///
+    /// These can be combined with other [GreenStatistics], but are subject to
+    /// the loss of precision which the local moments avoid.
+    pub fn global(&self) -> GreenStatistics {
+        self.local
+            .transform(Affine::translate(self.origin.to_vec2()))
+    }
+}
+
+impl CurveStatistics for RobustStatistics {
+    fn area(&self) -> f64 {
+        self.local.area()
+    }
+
+    fn center_of_mass(&self) -> Point {
+        self.origin + self.local.center_of_mass().to_vec2()
+    }
+
+    fn variance(&self) -> Vec2 {
+        self.local.variance()
+    }
+
+    fn covariance(&self) -> f64 {
+        self.local.covariance()
+    }
+
+    fn skewness(&self) -> Vec2 {
+        self.local.skewness()
+    }
+
+    fn kurtosis(&self) -> Vec2 {
+        self.local.kurtosis()
+    }
+}
+
+/// Neumaier's compensated summation, applied to each moment
+#[derive(Default)]
+struct CompensatedSum {
+    sum: MomentTable,
+    compensation: MomentTable,
+}
+
+impl CompensatedSum {
+    fn add(&mut self, statistics: &GreenStatistics) {
+        let rows = self.sum.iter_mut().zip(&mut self.compensation);
+        for ((sums, compensations), values) in rows.zip(statistics.moment_table()) {
+            for ((sum, compensation), value) in sums.iter_mut().zip(compensations).zip(values) {
+                let total = *sum + value;
+                *compensation += if sum.abs() >= value.abs() {
+                    (*sum - total) + value
+                } else {
+                    (value - total) + *sum
+                };
+                *sum = total;
+            }
+        }
+    }
+
+    fn total(&self) -> GreenStatistics {
+        let mut table = self.sum;
+        for (row, compensations) in table.iter_mut().zip(&self.compensation) {
+            for (moment, compensation) in row.iter_mut().zip(compensations) {
+                *moment += compensation;
+            }
+        }
+        GreenStatistics::from_table(&table)
+    }
+}
+
+impl<'a, T: 'a> ComputeRobustStatistics<'a> for T
+where
+    &'a T: IntoIterator<Item = PathEl>,
+{
+    fn robust_statistics(&'a self) -> RobustStatistics {
+        let mut anchor = None;
+        let mut total = CompensatedSum::default();
+        for contour in split_contours(self) {
+            let mut path = contour.path;
+            if !contour.closed {
+                path.close_path();
+            }
+            let origin = match path.elements().first() {
+                Some(PathEl::MoveTo(p)) => *p,
+                _ => Point::ZERO,
+            };
+            let anchor = *anchor.get_or_insert(origin);
+            // Integrate each segment relative to the contour's start point
+            let to_local = Affine::translate(-origin.to_vec2());
+            let mut moments = CompensatedSum::default();
+            for seg in path.segments() {
+                let mut contribution = GreenStatistics::default();
+                contribution.add_segment(to_local * seg);
+                moments.add(&contribution);
+            }
+            // Move the contour's moments to the common anchor by the parallel axis theorem
+            total.add(
+                &moments
+                    .total()
+                    .transform(Affine::translate(origin - anchor)),
+            );
+        }
+        let origin = anchor.unwrap_or(Point::ZERO);
+        let local = total.total();
+        if local.area() == 0.0 {
+            return RobustStatistics { origin, local };
+        }
+        let offset = local.center_of_mass().to_vec2();
+        RobustStatistics {
+            origin: origin + offset,
+            local: local.transform(Affine::translate(-offset)),
+        }
+    }
+}
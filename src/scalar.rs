@@ -0,0 +1,60 @@
+use std::fmt::Debug;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+/// A numeric type in which statistics can be accumulated
+///
+/// This is implemented for `f32` and `f64`. Implementing it for an extended
+/// precision or exact type lets the same formulas run in that type, e.g. to
+/// verify results computed in `f64`. [Default::default] must return zero.
+pub trait Scalar:
+    Copy
+    + Debug
+    + Default
+    + PartialEq
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+{
+    /// Convert from an `f64`, such as a coordinate or a constant in a formula
+    fn from_f64(value: f64) -> Self;
+
+    /// Convert to an `f64`, for reporting statistics
+    fn to_f64(self) -> f64;
+
+    /// Raise to a non-negative integer power
+    fn powi(self, n: i32) -> Self {
+        (0..n).fold(Self::from_f64(1.0), |acc, _| acc * self)
+    }
+}
+
+impl Scalar for f64 {
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn powi(self, n: i32) -> Self {
+        f64::powi(self, n)
+    }
+}
+
+impl Scalar for f32 {
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn powi(self, n: i32) -> Self {
+        f32::powi(self, n)
+    }
+}
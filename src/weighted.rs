@@ -0,0 +1,272 @@
+use kurbo::{PathEl, Point, Vec2};
+
+use crate::{CurveStatistics, StatisticsError};
+
+/// How each control point counts towards [WeightedControlStatistics]
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub enum ControlWeighting {
+    /// Every control point counts equally, as in [ControlStatistics](crate::ControlStatistics)
+    #[default]
+    Uniform,
+    /// Only on-curve points are counted
+    OnCurve,
+    /// On-curve points have a weight of one, and off-curve points the given weight
+    OffCurve(f64),
+    /// Each point is weighted by half the total length of the control polygon
+    /// edges which meet at it, so that the points sample the control polygon evenly
+    EdgeLength,
+}
+
+/// Statistics of weighted control points, kept as running sums
+///
+/// Unlike [ControlStatistics](crate::ControlStatistics), this does not store the
+/// points, so it takes constant memory however long the path. With
+/// [ControlWeighting::Uniform] it gives the same results.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct WeightedControlStatistics {
+    /// The total weight of the points; with uniform weighting, the number of points
+    pub weight: f64,
+    /// The sum of the squared weights
+    pub weight_squared: f64,
+    pub sum_x: f64,
+    pub sum_y: f64,
+    pub sum_xx: f64,
+    pub sum_xy: f64,
+    pub sum_yy: f64,
+    pub sum_xxx: f64,
+    pub sum_yyy: f64,
+    pub sum_xxxx: f64,
+    pub sum_yyyy: f64,
+    /// Twice the signed area of the control polygons
+    twice_area: f64,
+}
+
+impl WeightedControlStatistics {
+    fn add_point(&mut self, p: Point, weight: f64) {
+        if weight == 0.0 {
+            return;
+        }
+        self.weight += weight;
+        self.weight_squared += weight * weight;
+        self.sum_x += weight * p.x;
+        self.sum_y += weight * p.y;
+        self.sum_xx += weight * p.x * p.x;
+        self.sum_xy += weight * p.x * p.y;
+        self.sum_yy += weight * p.y * p.y;
+        self.sum_xxx += weight * p.x.powi(3);
+        self.sum_yyy += weight * p.y.powi(3);
+        self.sum_xxxx += weight * p.x.powi(4);
+        self.sum_yyyy += weight * p.y.powi(4);
+    }
+
+    /// The denominator of the unbiased variance, treating the weights as reliability weights
+    ///
+    /// With uniform weights this is one less than the number of points.
+    fn sample_weight(&self) -> f64 {
+        self.weight - self.weight_squared / self.weight
+    }
+}
+
+impl CurveStatistics for WeightedControlStatistics {
+    /// Find the area of the control polygons
+    ///
+    /// This is the same whatever the weighting.
+    fn area(&self) -> f64 {
+        self.twice_area / 2.0
+    }
+
+    /// Find the weighted mean of the control points
+    fn center_of_mass(&self) -> Point {
+        Point::new(self.sum_x / self.weight, self.sum_y / self.weight)
+    }
+
+    /// Find the (sample) variance of the control points
+    fn variance(&self) -> Vec2 {
+        let denominator = self.sample_weight();
+        if self.weight == 0.0 || denominator <= 0.0 {
+            return Vec2::ZERO;
+        }
+        Vec2::new(
+            (self.sum_xx - (self.sum_x * self.sum_x) / self.weight) / denominator,
+            (self.sum_yy - (self.sum_y * self.sum_y) / self.weight) / denominator,
+        )
+    }
+
+    /// Find the (sample) covariance of the control points
+    fn covariance(&self) -> f64 {
+        let denominator = self.sample_weight();
+        if self.weight == 0.0 || denominator <= 0.0 {
+            return 0.0;
+        }
+        (self.sum_xy - self.sum_x * self.sum_y / self.weight) / denominator
+    }
+
+    /// Find the skewness of the control points
+    ///
+    /// Uses the population (biased) third standardized moment.
+    fn skewness(&self) -> Vec2 {
+        let mean = self.center_of_mass();
+        let w = self.weight;
+        let skewness = |m1: f64, s2: f64, s3: f64| {
+            let m2 = s2 / w - m1 * m1;
+            (s3 / w - 3.0 * m1 * s2 / w + 2.0 * m1.powi(3)) / m2.powf(1.5)
+        };
+        Vec2::new(
+            skewness(mean.x, self.sum_xx, self.sum_xxx),
+            skewness(mean.y, self.sum_yy, self.sum_yyy),
+        )
+    }
+
+    /// Find the excess kurtosis of the control points
+    ///
+    /// Uses the population (biased) fourth standardized moment.
+    fn kurtosis(&self) -> Vec2 {
+        let mean = self.center_of_mass();
+        let w = self.weight;
+        let kurtosis = |m1: f64, s2: f64, s3: f64, s4: f64| {
+            let m2 = s2 / w - m1 * m1;
+            (s4 / w - 4.0 * m1 * s3 / w + 6.0 * m1 * m1 * s2 / w - 3.0 * m1.powi(4)) / m2.powi(2)
+                - 3.0
+        };
+        Vec2::new(
+            kurtosis(mean.x, self.sum_xx, self.sum_xxx, self.sum_xxxx),
+            kurtosis(mean.y, self.sum_yy, self.sum_yyy, self.sum_yyyy),
+        )
+    }
+
+    /// Check that some weight has been given to finite points
+    fn check(&self) -> Result<(), StatisticsError> {
+        if !(self.weight.is_finite() && self.sum_x.is_finite() && self.sum_y.is_finite()) {
+            Err(StatisticsError::NonFinite)
+        } else if self.weight == 0.0 {
+            Err(StatisticsError::EmptyInput)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Accumulates [WeightedControlStatistics] one path element at a time
+///
+/// Contours are split as in [ControlStatistics](crate::ControlStatistics): each
+/// [PathEl::MoveTo] starts a new one, as does drawing after a [PathEl::ClosePath].
+#[derive(Debug, Clone)]
+pub struct ControlAccumulator {
+    weighting: ControlWeighting,
+    statistics: WeightedControlStatistics,
+    /// The first point of the current contour, and its edge-length weight so far
+    first: Option<(Point, f64)>,
+    /// The latest point of the current contour, and its edge-length weight so far
+    previous: Option<(Point, f64)>,
+    /// The number of points in the current contour
+    contour_len: usize,
+    /// The start point of the current contour, from which drawing continues after
+    /// a [PathEl::ClosePath]
+    start: Point,
+    closed: bool,
+}
+
+impl ControlAccumulator {
+    pub fn new(weighting: ControlWeighting) -> Self {
+        ControlAccumulator {
+            weighting,
+            statistics: WeightedControlStatistics::default(),
+            first: None,
+            previous: None,
+            contour_len: 0,
+            start: Point::ZERO,
+            closed: true,
+        }
+    }
+
+    /// Add a path element
+    pub fn add(&mut self, el: PathEl) {
+        match el {
+            PathEl::MoveTo(p) => {
+                self.end_contour();
+                self.closed = false;
+                self.start = p;
+                self.add_point(p, true);
+            }
+            PathEl::LineTo(p) => self.add_point(p, true),
+            PathEl::QuadTo(p1, p2) => {
+                self.add_point(p1, false);
+                self.add_point(p2, true);
+            }
+            PathEl::CurveTo(p1, p2, p3) => {
+                self.add_point(p1, false);
+                self.add_point(p2, false);
+                self.add_point(p3, true);
+            }
+            PathEl::ClosePath => self.closed = true,
+        }
+    }
+
+    /// Finish the last contour and return the statistics
+    pub fn finish(mut self) -> WeightedControlStatistics {
+        self.end_contour();
+        self.statistics
+    }
+
+    fn add_point(&mut self, p: Point, on_curve: bool) {
+        if self.closed {
+            // As in ControlStatistics, the new contour begins at the start point
+            self.end_contour();
+            self.closed = false;
+            self.add_point(self.start, true);
+        }
+        let weight = match self.weighting {
+            ControlWeighting::Uniform => 1.0,
+            ControlWeighting::OnCurve if on_curve => 1.0,
+            ControlWeighting::OnCurve => 0.0,
+            ControlWeighting::OffCurve(_) if on_curve => 1.0,
+            ControlWeighting::OffCurve(weight) => weight,
+            ControlWeighting::EdgeLength => 0.0,
+        };
+        let Some((previous, previous_weight)) = self.previous else {
+            self.first = Some((p, 0.0));
+            self.previous = Some((p, 0.0));
+            self.contour_len = 1;
+            self.statistics.add_point(p, weight);
+            return;
+        };
+        self.statistics.twice_area += previous.x * p.y - p.x * previous.y;
+        let half_edge = (p - previous).hypot() / 2.0;
+        if self.weighting == ControlWeighting::EdgeLength {
+            // A point's weight is only known once both of its edges are; the first
+            // point's other edge is the one which closes the contour
+            if self.contour_len == 1 {
+                self.first = Some((previous, previous_weight + half_edge));
+            } else {
+                self.statistics
+                    .add_point(previous, previous_weight + half_edge);
+            }
+        } else {
+            self.statistics.add_point(p, weight);
+        }
+        self.previous = Some((p, half_edge));
+        self.contour_len += 1;
+    }
+
+    fn end_contour(&mut self) {
+        if let (Some((first, first_weight)), Some((last, last_weight))) =
+            (self.first.take(), self.previous.take())
+        {
+            self.statistics.twice_area += last.x * first.y - first.x * last.y;
+            if self.weighting == ControlWeighting::EdgeLength && self.contour_len > 1 {
+                let half_edge = (first - last).hypot() / 2.0;
+                self.statistics.add_point(last, last_weight + half_edge);
+                self.statistics.add_point(first, first_weight + half_edge);
+            }
+        }
+        self.contour_len = 0;
+    }
+}
+
+impl Extend<PathEl> for ControlAccumulator {
+    fn extend<I: IntoIterator<Item = PathEl>>(&mut self, iter: I) {
+        for el in iter {
+            self.add(el);
+        }
+    }
+}